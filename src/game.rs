@@ -1,23 +1,58 @@
 mod io;
 mod input;
-
-use crate::game::input::Input;
+mod bench;
+mod protocol;
+pub mod auto_play;
+pub mod multiplayer;
+pub mod daily;
+pub mod world_code;
+mod leaderboard;
+mod save;
+mod render;
+
+use crate::game::input::{Command, ExportInput, Input};
+use crate::game::bench::Bench;
+use crate::game::render::Render;
+use crate::game::protocol::Protocol;
+use crate::game::auto_play::AutoPlay;
+use crate::game::multiplayer::{HostWorld, Mode, Multiplayer, NetworkEvent};
+use crate::game::daily::Date;
+use crate::game::leaderboard::{Entry, Leaderboard};
+use crate::game::save::Save;
+use crate::view::export::Export;
 use crate::grid::cell_builder::CellBuilder;
-use crate::view::View;
-use crate::helper::{PlaceI32, SizeI32, SizeUsize};
+use crate::grid::density::DensityCurve;
+use crate::grid::topology::Topology;
+use crate::grid::generator::GeneratorKind;
+use crate::view::{View, ViewConfig};
+use crate::helper::{PlaceI32, RectI32, SizeI32, SizeUsize};
 use crate::grid::Grid;
-use crate::grid::cell::{Cell, CellState, CellValue};
+use crate::grid::cell::{CellState, CellValue};
+use crate::solver::{Analysis, Hint, Solver};
 use std::sync::mpsc::Sender;
 use std::time::{self, Duration};
 use clap::Parser;
+use serde::{Deserialize, Serialize};
 use crossterm::terminal;
 use io::{Io, IoEvent};
 
+#[cfg(test)]
+mod tests;
+
 pub enum Action {
     MoveCursor(Direction),
     Reveal,
     Flag,
     RevealAdjacent,
+    Hint,
+    Save,
+    Export,
+    ToggleHeatMap,
+    ToggleAutoPlay,
+    AutoPlayFaster,
+    AutoPlaySlower,
+    AutoPlayStep,
+    ToggleFollow,
     Reset,
     Resize(SizeUsize),
 }
@@ -27,16 +62,49 @@ pub enum  Direction {
     Right,
     Down,
     Up,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
 }
 
-#[derive(Debug)]
-pub enum MineCount {
-    Zero,
-    One, Two, Three, Four,
-    Five, Six, Seven, Eight,
+/// Mines among a cell's neighbours, up to `Topology::MAX_MINE_COUNT`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MineCount(u8);
+
+impl MineCount {
+    pub const ZERO: MineCount = MineCount(0);
+
+    pub fn count(&self) -> u8 {
+        self.0
+    }
 }
 
+/// Overlay colouring frontier cells by their mine probability.
 #[derive(Clone, Copy, Debug)]
+pub enum HeatMap {
+    Off,
+    Colours,
+    /// also show the probability of the cell under the cursor
+    ColoursAndReadout,
+}
+
+/// Settings that make a world, from the command line or from a daily
+/// challenge, world code, save or host.
+#[derive(Clone, Debug)]
+pub struct WorldConfig {
+    pub mine_concentration: f64,
+    /// random if not given
+    pub seed: Option<u64>,
+    pub generator: GeneratorKind,
+    /// version of world generation, see `GeneratorKind::VERSION`
+    pub version: u8,
+    pub density_curve: Option<DensityCurve>,
+    pub topology: Topology,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum GameState {
     Underway,
     Lost,
@@ -48,10 +116,28 @@ pub struct Game {
     grid: Grid,
     cursor: PlaceI32,
     origin: PlaceI32,
+    /// the score; one for each revealed cell, or with a density curve,
+    /// more for cells where mines are denser
     revealed_cell_count: u32,
+    /// part of a point earned towards the score
+    score_fraction: f64,
+    hint: Option<Hint>,
+    hints_used: u32,
+    heat_map: HeatMap,
+    heat_map_analysis: Option<(RectI32, Analysis)>,
+    auto_play: AutoPlay,
+    changes: Option<Vec<PlaceI32>>,
+    multiplayer: Option<Multiplayer>,
+    /// day of the daily challenge being played
+    daily: Option<Date>,
+    /// add the game to the leaderboard when it ends
+    record_scores: bool,
+    /// message for the player, until their next action
+    notice: Option<String>,
     start_instant: time::Instant,
     end_instant: Option<time::Instant>,
     mine_concentration: f64,
+    generator: GeneratorKind,
     cell_builder: CellBuilder,
     seed: Option<u64>,
     window_size: SizeUsize,
@@ -66,47 +152,159 @@ impl Game {
         height: 3,
     };
 
+    /// Window size for games that are not rendered to a terminal.
+    pub const HEADLESS_WINDOW_SIZE: SizeUsize = SizeUsize {
+        width:  80,
+        height: 24,
+    };
+
+    /// Distance from the cursor within which hints look for numbers.
+    pub const HINT_RADIUS: i32 = 20;
+
     pub fn start() -> std::io::Result<()> {
         let input = Input::parse();
+        match input.command {
+            Some(Command::Bench(bench_input)) => {
+                return Bench::new(bench_input).run(std::io::stdout());
+            },
+            Some(Command::Leaderboard(leaderboard_input)) => {
+                let daily = leaderboard_input.daily.map(|date| date.unwrap_or_else(Date::today));
+                return Leaderboard::load()?.print(std::io::stdout(), daily);
+            },
+            Some(Command::Export(export_input)) => {
+                return Self::export_save(export_input);
+            },
+            Some(Command::Render(render_input)) => {
+                return Render::new(render_input).run(std::io::stdout().lock());
+            },
+            None => (),
+        }
+        if input.protocol {
+            input.generator.check(input.topology)
+                .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidInput, error))?;
+            let mut protocol = Protocol::new(input.world_config());
+            return protocol.run(std::io::stdin().lock(), std::io::stdout());
+        }
         let window_size = terminal::window_size().expect("failed to get terminal size");
         let window_size: SizeUsize = SizeUsize {
             width:  window_size.columns as usize,
             height: window_size.rows    as usize,
         };
-        let mut game = Self::new(
-            input.mine_concentration, input.seed,
-            window_size, input.light_mode,
-            None,
+        let auto_play = AutoPlay::new(
+            input.auto_play, input.auto_play_speed, input.auto_play_guess,
         );
+        let daily = input.daily.map(|date| date.unwrap_or_else(Date::today));
+        let input_config = input.world_config();
+        let save = input.load.map(Save::load).transpose()?;
+        let mut config = match (daily, input.world, &save) {
+            (Some(date), _, _) => WorldConfig {
+                mine_concentration: Date::MINE_CONCENTRATION,
+                seed: Some(date.seed()),
+                generator: GeneratorKind::Uniform,
+                version: GeneratorKind::VERSION,
+                density_curve: None,
+                topology: Topology::Square,
+            },
+            (_, Some(world), _) => WorldConfig {
+                mine_concentration: world.mine_concentration,
+                seed: Some(world.seed),
+                generator: world.generator_kind(),
+                version: world.version,
+                density_curve: None,
+                topology: world.topology(),
+            },
+            (_, _, Some(save)) => WorldConfig {
+                mine_concentration: save.mine_concentration,
+                seed: Some(save.seed),
+                generator: save.generator.clone(),
+                version: save.version,
+                density_curve: save.density_curve,
+                topology: save.topology,
+            },
+            (None, None, None) => input_config,
+        };
+        let mut multiplayer = None;
+        if let Some(address) = input.host {
+            let mode = if input.race {
+                Mode::Race { time_limit: input.time_limit }
+            } else {
+                Mode::Coop { rule: input.mine_rule }
+            };
+            multiplayer = Some(Multiplayer::host(address, mode)?);
+        } else if let Some(address) = input.publish {
+            multiplayer = Some(Multiplayer::host(address, Mode::Publish)?);
+        } else if let Some(address) = input.join.or(input.spectate.clone()) {
+            let (joined, world) = Multiplayer::join(address)?;
+            if input.spectate.is_some() && !joined.spectating() {
+                return Err(std::io::Error::other("that game is not published for spectators"));
+            }
+            config = world.config();
+            multiplayer = Some(joined);
+        }
+        config.generator.check(config.topology)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidInput, error))?;
+        if let GeneratorKind::Layout(layout) = &config.generator {
+            // the solver judges risk by the concentration
+            config.mine_concentration = layout.mine_concentration();
+        }
+        let mut game = Self::new(config, window_size, input.light_mode, auto_play, None);
+        if multiplayer.as_ref().is_some_and(Multiplayer::shares_grid) {
+            game.record_changes();
+        }
+        // only count games played alone, by hand, in the usual kind of world
+        game.record_scores = game.generator == GeneratorKind::Uniform &&
+            game.cell_builder.density_curve.is_none() && game.cell_builder.topology == Topology::Square &&
+            !input.auto_play &&
+            multiplayer.as_ref().is_none_or(|multiplayer| {
+                matches!(multiplayer.mode, Mode::Publish) && !multiplayer.spectating()
+            });
+        game.multiplayer = multiplayer;
+        game.daily = daily;
+        if let Some(save) = save {
+            game.restore(save);
+        }
         game.run(std::io::stdout())
     }
 
     pub fn new(
-        mine_concentration: f64,
-        seed: Option<u64>,
+        config: WorldConfig,
         window_size: SizeUsize,
         light_mode: bool,
+        auto_play: AutoPlay,
         tx_panic: Option<Sender<IoEvent>>,
     ) -> Game {
+        let WorldConfig { mine_concentration, seed, generator, version, density_curve, topology } = config;
         let max_cursor_displacement =
             Self::max_cursor_displacement(window_size);
         let cell_builder =
             CellBuilder::new(
-                mine_concentration, seed,
+                mine_concentration, seed, &generator, version, density_curve, topology,
                 |message: &'static str| {
                     Self::send_panic(&tx_panic, message);
                 },
             );
-        let grid = Grid::new(cell_builder);
+        let grid = Grid::new(cell_builder.clone());
         let mut game = Game {
             state: GameState::Underway,
             grid,
             cursor: PlaceI32 { x: 0, y: 0 },
             origin: PlaceI32 { x: 0, y: 0 },
             revealed_cell_count: 0,
+            score_fraction: 0f64,
+            hint: None,
+            hints_used: 0,
+            heat_map: HeatMap::Off,
+            heat_map_analysis: None,
+            auto_play,
+            changes: None,
+            multiplayer: None,
+            daily: None,
+            record_scores: false,
+            notice: None,
             start_instant: time::Instant::now(),
             end_instant: None,
             mine_concentration,
+            generator,
             cell_builder,
             seed,
             window_size,
@@ -132,29 +330,186 @@ impl Game {
     }
 
     pub fn action(&mut self, action: Action) {
+        if let Action::Reveal | Action::Flag | Action::RevealAdjacent = action {
+            self.invalidate_analysis();
+        }
+        if !matches!(action, Action::Resize(_) | Action::AutoPlayStep) {
+            self.notice = None;
+        }
+        let state_before = self.state;
+        if let Some(multiplayer) = &mut self.multiplayer &&
+           multiplayer.intercept(&action, self.cursor, state_before) {
+            return;
+        }
+        let reset = matches!(action, Action::Reset);
         match (self.state, action) {
             (_,                   Action::Resize(new_size)) => self.resize(new_size),
             (_, _) if self.window_too_small(self.window_size) => (),
             (GameState::Underway, Action::Flag)           => self.toggle_flag(self.cursor),
             (GameState::Underway, Action::Reveal)         => self.reveal(self.cursor),
             (GameState::Underway, Action::RevealAdjacent) => self.reveal_adjacent(self.cursor),
+            (GameState::Underway, Action::Hint)           => self.hint(),
+            (GameState::Underway, Action::AutoPlayStep)   => self.auto_play_step(),
+            (_,                   Action::Save)           => self.save(),
+            (_,                   Action::Export)         => self.export(),
+            (_,                   Action::ToggleHeatMap)  => self.toggle_heat_map(),
+            (_,                   Action::ToggleAutoPlay) => self.toggle_auto_play(),
+            (_,                   Action::AutoPlayFaster) => self.auto_play.faster(),
+            (_,                   Action::AutoPlaySlower) => self.auto_play.slower(),
+            (_,                   Action::ToggleFollow)   => self.toggle_follow(),
             (_,                   Action::MoveCursor(direction)) => self.move_cursor(direction),
             (_,                   Action::Reset) => self.reset(),
             _ => (),
         }
+        self.sync_multiplayer(state_before, reset);
+        self.update_heat_map();
+    }
+
+    fn save(&mut self) {
+        let path = format!("minesweeper-{}.json", self.cell_builder.seed);
+        let save = Save {
+            seed: self.cell_builder.seed,
+            mine_concentration: self.mine_concentration,
+            generator: self.generator.clone(),
+            version: self.cell_builder.version,
+            density_curve: self.cell_builder.density_curve,
+            topology: self.cell_builder.topology,
+            state: self.state,
+            score: self.revealed_cell_count,
+            hints_used: self.hints_used,
+            cells: Save::cells(&self.grid),
+        };
+        self.notice = Some(match save.write(&path) {
+            Ok(()) => format!("saved to {path}"),
+            Err(error) => format!("failed to save: {error}"),
+        });
+    }
+
+    fn restore(&mut self, save: Save) {
+        save.apply(&mut self.grid);
+        self.revealed_cell_count = save.score;
+        self.hints_used = save.hints_used;
+        if let GameState::Lost = save.state {
+            self.state = GameState::Lost;
+            self.end_instant = Some(self.start_instant);
+        }
+    }
+
+    fn export(&mut self) {
+        let show_mines = matches!(self.state, GameState::Lost);
+        let export = match Export::new(&self.grid, None, show_mines, self.light_mode) {
+            Ok(Some(export)) => export,
+            Ok(None) => return,
+            Err(error) => {
+                self.notice = Some(format!("failed to export: {error}"));
+                return;
+            },
+        };
+        let path = format!("minesweeper-{}", self.cell_builder.seed);
+        let result = std::fs::File::create(format!("{path}.png"))
+            .and_then(|file| export.png(std::io::BufWriter::new(file)))
+            .and_then(|()| std::fs::File::create(format!("{path}.svg")))
+            .and_then(|file| export.svg(std::io::BufWriter::new(file)));
+        self.notice = Some(match result {
+            Ok(()) => format!("exported to {path}.png and {path}.svg"),
+            Err(error) => format!("failed to export: {error}"),
+        });
+    }
+
+    fn export_save(input: ExportInput) -> std::io::Result<()> {
+        let save = Save::load(&input.save)?;
+        let show_mines = matches!(save.state, GameState::Lost);
+        let grid = save.grid();
+        let Some(export) = Export::new(&grid, input.area, show_mines, input.light_mode)? else {
+            return Err(std::io::Error::other("nothing to export"));
+        };
+        let output = input.output.unwrap_or_else(|| input.save.with_extension("png"));
+        let file = std::io::BufWriter::new(std::fs::File::create(&output)?);
+        match output.extension().and_then(|extension| extension.to_str()) {
+            Some("svg") => export.svg(file),
+            _ => export.png(file),
+        }
+    }
+
+    /// Called when the timer display changes.
+    pub fn second(&mut self) {
+        self.sync_multiplayer(self.state, false);
+    }
+
+    fn toggle_follow(&mut self) {
+        let Some(mut multiplayer) = self.multiplayer.take() else { return; };
+        multiplayer.toggle_follow(self);
+        self.multiplayer = Some(multiplayer);
+    }
+
+    fn sync_multiplayer(&mut self, state_before: GameState, reset: bool) {
+        let Some(mut multiplayer) = self.multiplayer.take() else { return; };
+        let player = multiplayer.player;
+        multiplayer.sync(self, player, state_before, reset);
+        self.multiplayer = Some(multiplayer);
+    }
+
+    pub fn network(&mut self, event: NetworkEvent) {
+        let Some(mut multiplayer) = self.multiplayer.take() else { return; };
+        multiplayer.handle(self, event);
+        self.multiplayer = Some(multiplayer);
+        self.update_heat_map();
+    }
+
+    /// Forgets the hint and probabilities once the grid has changed.
+    fn invalidate_analysis(&mut self) {
+        self.hint = None;
+        self.heat_map_analysis = None;
     }
 
     fn lose(&mut self) {
         self.state = GameState::Lost;
-        self.end_instant = Some(time::Instant::now());
+        let end_instant = time::Instant::now();
+        self.end_instant = Some(end_instant);
+        if self.record_scores {
+            // a leaderboard that can't be written shouldn't end the game
+            let _ = Leaderboard::record(self.entry(end_instant), self.daily);
+        }
+    }
+
+    /// Records a game left underway on the leaderboard, as if it were lost.
+    pub fn quit(&mut self) {
+        if let Some(entry) = self.quit_entry() {
+            let _ = Leaderboard::record(entry, self.daily);
+        }
+    }
+
+    /// The entry for a game left underway, if it scored without any hints.
+    fn quit_entry(&self) -> Option<Entry> {
+        let counts = self.record_scores && matches!(self.state, GameState::Underway) &&
+            self.revealed_cell_count > 0 && self.hints_used == 0;
+        counts.then(|| self.entry(time::Instant::now()))
+    }
+
+    fn entry(&self, end_instant: time::Instant) -> Entry {
+        Entry {
+            score: self.revealed_cell_count,
+            time: end_instant.duration_since(self.start_instant).as_secs(),
+            hints_used: self.hints_used,
+            seed: self.cell_builder.seed,
+            mine_concentration: self.mine_concentration,
+            version: self.cell_builder.version,
+        }
     }
 
     fn move_cursor(&mut self, direction: Direction) {
+        // on hexagons y ± 1 is always a neighbour, half a cell to one side
+        // or the other, so up and down zigzag within a column
+        let tiling = self.grid.topology().tiling();
         match direction {
             Direction::Left   => self.cursor.x -= 1,
             Direction::Right  => self.cursor.x += 1,
             Direction::Down   => self.cursor.y -= 1,
             Direction::Up     => self.cursor.y += 1,
+            Direction::UpLeft    => self.cursor = tiling.diagonal(self.cursor, true,  false),
+            Direction::UpRight   => self.cursor = tiling.diagonal(self.cursor, true,  true),
+            Direction::DownLeft  => self.cursor = tiling.diagonal(self.cursor, false, false),
+            Direction::DownRight => self.cursor = tiling.diagonal(self.cursor, false, true),
         };
         
         self.tether_origin();
@@ -165,8 +520,10 @@ impl Game {
         match cell.state {
             CellState::Hidden => cell.flag(),
             CellState::Flagged => cell.unflag(),
-            _ => (),
+            _ => return,
         }
+        drop(cell);
+        self.record_change(place);
     }
 
     fn reveal(&mut self, place: PlaceI32) {
@@ -174,8 +531,11 @@ impl Game {
     }
 
     fn reveal_tracked(&mut self, place: PlaceI32, mut revealed: u32) {
-        if let CellState::Revealed = self.grid.get(place).state { return };
+        if let CellState::Revealed = self.grid.peek_state(place) { return };
         
+        if let CellState::Hidden = self.grid.peek_state(place) {
+            self.record_change(place);
+        }
         self.grid.get_mut(place).reveal();
 
         revealed += 1;
@@ -185,49 +545,161 @@ impl Game {
             return;
         }
         
-        if let CellValue::Mine = self.grid.get(place).value {
+        if let CellValue::Mine = self.grid.peek(place).value {
             self.lose();
             return;
         }
-        self.revealed_cell_count += 1;
+        self.score(place);
 
-        let MineCount::Zero = Self::mine_count(&self.grid, place) else { return; };
+        if Self::mine_count(&self.grid, place) != MineCount::ZERO { return; }
 
-        for i in -1..=1 {
-            for j in -1..=1 {
-                if let (0, 0) = (i, j) {
-                    continue;
-                }
-                let place = PlaceI32 { x: place.x + i, y: place.y + j };
-                self.reveal_tracked(place, revealed);
-            }
+        let neighbours: Vec<PlaceI32> = self.grid.neighbours(place).collect();
+        for place in neighbours {
+            self.reveal_tracked(place, revealed);
         }
     }
 
     // in original minesweeper, doesn't reveal cells
     // when there are too many flags around the cell 
     fn reveal_adjacent(&mut self, place: PlaceI32) {
-        let CellState::Revealed = self.grid.get(place).state else { return; };
-        for i in -1..=1 {
-            for j in -1..=1 {
-                if let (0, 0) = (i, j) {
-                    continue;
-                }
-                let place = PlaceI32 { x: place.x + i, y: place.y + j };
+        let CellState::Revealed = self.grid.peek_state(place) else { return; };
+        let neighbours: Vec<PlaceI32> = self.grid.neighbours(place).collect();
+        for place in neighbours {
+            if let CellState::Flagged = self.grid.peek_state(place) { continue; }
 
-                if let CellState::Flagged = self.grid.get(place).state { continue; }
+            self.reveal(place);
+        }
+    }
 
-                self.reveal(place);
-            }
+    /// Adds the points for revealing `place`.
+    fn score(&mut self, place: PlaceI32) {
+        self.score_fraction += match self.cell_builder.density_curve {
+            Some(_) if self.mine_concentration > 0f64 =>
+                self.grid.mine_concentration(place) / self.mine_concentration,
+            _ => 1f64,
+        };
+        let points = self.score_fraction.floor();
+        self.score_fraction -= points;
+        self.revealed_cell_count += points as u32;
+    }
+
+    fn hint(&mut self) {
+        if self.hint.is_some() { return; }
+        let solver = Solver::new(&self.grid, self.grid.mine_concentration(self.cursor));
+        self.hint = solver.hint(self.cursor, Self::HINT_RADIUS);
+        if self.hint.is_some() {
+            self.hints_used += 1;
+        }
+    }
+
+    fn toggle_auto_play(&mut self) {
+        self.auto_play.toggle();
+        // games the computer helped play don't go on the leaderboard
+        self.record_scores = false;
+    }
+
+    fn auto_play_step(&mut self) {
+        let mut auto_play = self.auto_play;
+        let action = auto_play.next_action(self);
+        self.auto_play = auto_play;
+        if let Some(action) = action {
+            self.action(action);
+        }
+    }
+
+    /// Time until the next auto-play step, if auto-play is running.
+    pub fn auto_play_interval(&self) -> Option<Duration> {
+        let GameState::Underway = self.state else { return None; };
+        if self.window_too_small(self.window_size) { return None; }
+        self.auto_play.interval()
+    }
+
+    fn toggle_heat_map(&mut self) {
+        self.heat_map = match self.heat_map {
+            HeatMap::Off               => HeatMap::Colours,
+            HeatMap::Colours           => HeatMap::ColoursAndReadout,
+            HeatMap::ColoursAndReadout => HeatMap::Off,
+        };
+    }
+
+    fn update_heat_map(&mut self) {
+        if let HeatMap::Off = self.heat_map {
+            self.heat_map_analysis = None;
+            return;
+        }
+        let area = self.viewport();
+        if let Some((analysed_area, _)) = &self.heat_map_analysis && *analysed_area == area {
+            return;
+        }
+        // numbers just outside the viewport constrain cells on its edge
+        let analysis = Solver::new(&self.grid, self.grid.mine_concentration(self.origin))
+            .analyse(area.grow(self.grid.topology().reach()));
+        self.heat_map_analysis = Some((area, analysis));
+    }
+
+    fn viewport(&self) -> RectI32 {
+        let matrix_size = View::matrix_size(self.window_size);
+        RectI32 {
+            corner: PlaceI32 {
+                x: self.origin.x - matrix_size.width  as i32 / 2,
+                y: self.origin.y - matrix_size.height as i32 / 2,
+            },
+            size: matrix_size.into(),
+        }
+    }
+
+    /// Starts collecting the places of cells whose state changes.
+    pub fn record_changes(&mut self) {
+        self.changes.get_or_insert_with(Vec::new);
+    }
+
+    pub fn take_changes(&mut self) -> Vec<PlaceI32> {
+        self.changes.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    fn record_change(&mut self, place: PlaceI32) {
+        if let Some(changes) = &mut self.changes {
+            changes.push(place);
         }
     }
 
     fn reset(&mut self) {
-        *self = Game::new(
-            self.mine_concentration, self.seed,
-            self.window_size,        self.light_mode,
-            self.tx_panic.clone(),
-        );
+        self.restart(self.world_config());
+    }
+
+    /// Starts again in the host's world, which may differ from the last.
+    fn reset_to(&mut self, world: HostWorld) {
+        self.restart(world.config());
+    }
+
+    /// The settings this game's world was made with.
+    fn world_config(&self) -> WorldConfig {
+        WorldConfig {
+            mine_concentration: self.mine_concentration,
+            seed: self.seed,
+            generator: self.generator.clone(),
+            version: self.cell_builder.version,
+            density_curve: self.cell_builder.density_curve,
+            topology: self.cell_builder.topology,
+        }
+    }
+
+    /// Starts a new game in the world of `config`, keeping the settings
+    /// chosen while playing.
+    fn restart(&mut self, config: WorldConfig) {
+        let heat_map = self.heat_map;
+        let mut auto_play = self.auto_play;
+        auto_play.restart();
+        let recording_changes = self.changes.is_some();
+        let multiplayer = self.multiplayer.take();
+        let (daily, record_scores) = (self.daily, self.record_scores);
+        *self = Game::new(config, self.window_size, self.light_mode, auto_play, self.tx_panic.clone());
+        self.heat_map = heat_map;
+        self.multiplayer = multiplayer;
+        (self.daily, self.record_scores) = (daily, record_scores);
+        if recording_changes {
+            self.record_changes();
+        }
     }
 
     fn resize(&mut self, new_size: SizeUsize) {
@@ -285,26 +757,12 @@ impl Game {
     }
 
     pub fn mine_count(grid: &Grid, place: PlaceI32) -> MineCount {
-        let mut count = 0;
-        for i in -1..=1 {
-            for j in -1..=1 {
-                if let (0, 0) = (i, j) {
-                    continue;
-                }
-                let place = PlaceI32 { x: place.x + i, y: place.y + j }; 
-                if let Cell { value: CellValue::Mine, .. } = *grid.get(place) {
-                    count += 1;
-                }
-            }
-        }
-        match count {
-            0 => MineCount::Zero,
-            1 => MineCount::One,   2 => MineCount::Two,
-            3 => MineCount::Three, 4 => MineCount::Four,
-            5 => MineCount::Five,  6 => MineCount::Six,
-            7 => MineCount::Seven, 8 => MineCount::Eight,
-            _ => unreachable!(), // we only check 8 tiles
-        }
+        Self::to_mine_count(grid.mine_count(place))
+    }
+
+    pub fn to_mine_count(count: u8) -> MineCount {
+        debug_assert!(count <= Topology::MAX_MINE_COUNT);
+        MineCount(count)
     }
 
     pub fn time_until_timer_update() -> time::Duration {
@@ -318,17 +776,37 @@ impl Game {
 
     pub fn view(&self) -> View {
         let window_too_small = self.window_too_small(self.window_size);
-        let show_mines = if let GameState::Lost = self.state { true } else { false };
-        let latest_game_instant = self.end_instant.unwrap_or_else(|| time::Instant::now());
+        let show_mines = matches!(self.state, GameState::Lost);
+        let latest_game_instant = self.end_instant.unwrap_or_else(time::Instant::now);
         let game_cursor = self.cursor;
-        View::new(
-            &self.grid,               self.window_size,
-            window_too_small,         self.origin,
-            game_cursor,              show_mines,
-            self.revealed_cell_count, self.start_instant,
-            latest_game_instant,      self.state,
-            self.cell_builder.seed,   self.light_mode,
-        )
+        let heat_map = self.heat_map_analysis.as_ref()
+            .map(|(_, analysis)| (analysis, matches!(self.heat_map, HeatMap::ColoursAndReadout)));
+        View::new(ViewConfig {
+            grid: &self.grid,
+            window_size: self.window_size,
+            window_too_small,
+            origin: self.origin,
+            game_cursor,
+            show_mines,
+            revealed_cell_count: self.revealed_cell_count,
+            start_instant: self.start_instant,
+            latest_game_instant,
+            game_state: self.state,
+            seed: self.cell_builder.seed,
+            light_mode: self.light_mode,
+            hint: self.hint.clone(),
+            hints_used: self.hints_used,
+            heat_map,
+            mine_concentration: self.mine_concentration,
+            generator: &self.generator,
+            version: self.cell_builder.version,
+            density_curve: self.cell_builder.density_curve,
+            topology: self.cell_builder.topology,
+            auto_play: self.auto_play,
+            multiplayer: self.multiplayer.as_ref(),
+            daily: self.daily,
+            notice: self.notice.clone(),
+        })
     }
 
     fn max_cursor_displacement(window_size: SizeUsize) -> SizeI32 {
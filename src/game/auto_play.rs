@@ -0,0 +1,140 @@
+use crate::game::{Action, Direction, Game, GameState};
+use crate::grid::cell::CellState;
+use crate::helper::PlaceI32;
+use crate::solver::{Hint, Solver, Verdict};
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Speed {
+    StepsPerSecond(u32),
+    Instant,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum AutoPlayState {
+    Off,
+    Running,
+    Paused,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Move {
+    Reveal,
+    Flag,
+}
+
+/// Lets the solver play by walking the cursor to its next move.
+#[derive(Clone, Copy, Debug)]
+pub struct AutoPlay {
+    pub state: AutoPlayState,
+    pub speed: Speed,
+    /// reveal the least risky cell when no move is certain
+    pub guess: bool,
+    target: Option<(PlaceI32, Move)>,
+}
+
+impl AutoPlay {
+    pub const MAX_STEPS_PER_SECOND: u32 = 1024;
+
+    pub fn new(running: bool, steps_per_second: u32, guess: bool) -> AutoPlay {
+        AutoPlay {
+            state: if running { AutoPlayState::Running } else { AutoPlayState::Off },
+            speed: match steps_per_second {
+                0 => Speed::Instant,
+                steps_per_second => Speed::StepsPerSecond(
+                    steps_per_second.min(Self::MAX_STEPS_PER_SECOND),
+                ),
+            },
+            guess,
+            target: None,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.state = match self.state {
+            AutoPlayState::Running => AutoPlayState::Paused,
+            AutoPlayState::Off | AutoPlayState::Paused => AutoPlayState::Running,
+        };
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = match self.speed {
+            Speed::StepsPerSecond(steps_per_second) if steps_per_second >= Self::MAX_STEPS_PER_SECOND => {
+                Speed::Instant
+            },
+            // speeds that aren't powers of two still stop at the maximum
+            Speed::StepsPerSecond(steps_per_second) => Speed::StepsPerSecond(
+                (steps_per_second * 2).min(Self::MAX_STEPS_PER_SECOND),
+            ),
+            Speed::Instant => Speed::Instant,
+        };
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = match self.speed {
+            Speed::StepsPerSecond(steps_per_second) => Speed::StepsPerSecond((steps_per_second / 2).max(1)),
+            Speed::Instant => Speed::StepsPerSecond(Self::MAX_STEPS_PER_SECOND),
+        };
+    }
+
+    /// Time between steps while running.
+    pub fn interval(&self) -> Option<Duration> {
+        let AutoPlayState::Running = self.state else { return None; };
+        Some(match self.speed {
+            Speed::StepsPerSecond(steps_per_second) => Duration::from_secs(1) / steps_per_second,
+            Speed::Instant => Duration::ZERO,
+        })
+    }
+
+    pub fn restart(&mut self) {
+        self.target = None;
+    }
+
+    /// Either moves the cursor one cell towards the planned move or makes it.
+    pub fn next_action(&mut self, game: &Game) -> Option<Action> {
+        if let GameState::Lost = game.state {
+            self.state = AutoPlayState::Paused;
+            return None;
+        }
+
+        let target = match self.target {
+            Some((place, _)) if matches!(game.grid.peek_state(place), CellState::Hidden) => self.target,
+            _ => self.plan(game),
+        };
+        let Some((place, next_move)) = target else {
+            self.state = AutoPlayState::Paused;
+            return None;
+        };
+        self.target = target;
+
+        let cursor = game.cursor;
+        let action = if cursor.x < place.x {
+            Action::MoveCursor(Direction::Right)
+        } else if cursor.x > place.x {
+            Action::MoveCursor(Direction::Left)
+        } else if cursor.y < place.y {
+            Action::MoveCursor(Direction::Up)
+        } else if cursor.y > place.y {
+            Action::MoveCursor(Direction::Down)
+        } else {
+            self.target = None;
+            match next_move {
+                Move::Reveal => Action::Reveal,
+                Move::Flag   => Action::Flag,
+            }
+        };
+        Some(action)
+    }
+
+    fn plan(&self, game: &Game) -> Option<(PlaceI32, Move)> {
+        let solver = Solver::new(&game.grid, game.grid.mine_concentration(game.cursor));
+        match solver.hint(game.cursor, Game::HINT_RADIUS)? {
+            Hint::Certain(finding) => Some((finding.place, match finding.verdict {
+                Verdict::Safe => Move::Reveal,
+                Verdict::Mine => Move::Flag,
+            })),
+            Hint::Guess { place, .. } if self.guess => Some((place, Move::Reveal)),
+            Hint::Guess { .. } => None,
+        }
+    }
+}
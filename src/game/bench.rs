@@ -0,0 +1,277 @@
+use crate::game::{Game, GameState};
+use self::grid::GridRun;
+use crate::game::auto_play::AutoPlay;
+use crate::game::input::{BenchInput, Format, Strategy};
+use crate::helper::PlaceI32;
+use crate::solver::{Finding, Hint, Solver, Verdict};
+use serde::Serialize;
+use std::ops::Range;
+use std::{io, time};
+
+mod grid;
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Outcome {
+    Lost,
+    /// no certain move was left and the strategy doesn't guess
+    Stuck,
+    MoveLimit,
+}
+
+#[derive(Debug, Serialize)]
+struct Run {
+    seed: u64,
+    mine_concentration: f64,
+    score: u32,
+    moves: u32,
+    guesses: u32,
+    outcome: Outcome,
+    seconds: f64,
+    cells_per_second: f64,
+    grid_bytes: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct Summary {
+    mine_concentration: f64,
+    runs: usize,
+    lost: usize,
+    score_min: u32,
+    score_p25: u32,
+    score_median: u32,
+    score_p75: u32,
+    score_max: u32,
+    score_mean: f64,
+    guesses_mean: f64,
+    cells_per_second: f64,
+    grid_bytes_max: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    runs: Vec<Run>,
+    summaries: Vec<Summary>,
+}
+
+/// Plays games with the solver without a terminal.
+#[derive(Debug)]
+pub struct Bench {
+    input: BenchInput,
+}
+
+impl Bench {
+    pub fn new(input: BenchInput) -> Bench {
+        Bench { input }
+    }
+
+    pub fn run(&self, mut buffer: impl io::Write) -> io::Result<()> {
+        self.input.generator.check(self.input.topology)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        if self.input.grid {
+            return self.run_grid(buffer);
+        }
+        let seeds = self.seeds()?;
+        let mut runs = Vec::new();
+        let mut summaries = Vec::new();
+        for &mine_concentration in self.input.mine_concentrations.iter() {
+            let first_run = runs.len();
+            for seed in seeds.clone() {
+                runs.push(self.play(seed, mine_concentration));
+            }
+            if let Some(summary) = Self::summarise(mine_concentration, &runs[first_run..]) {
+                summaries.push(summary);
+            }
+        }
+
+        match self.input.format {
+            Format::Csv => {
+                Self::write_csv(&mut buffer, &runs)?;
+                Self::write_summaries(&mut io::stderr(), &summaries)?;
+            },
+            Format::Json => {
+                serde_json::to_writer_pretty(&mut buffer, &Report { runs, summaries })?;
+                writeln!(buffer)?;
+            },
+        }
+        buffer.flush()
+    }
+
+    fn run_grid(&self, mut buffer: impl io::Write) -> io::Result<()> {
+        let seeds = self.seeds()?;
+        let runs: Vec<GridRun> = self.input.mine_concentrations.iter()
+            .flat_map(|&mine_concentration| seeds.clone().map(move |seed| (seed, mine_concentration)))
+            .map(|(seed, mine_concentration)| {
+                GridRun::new(seed, mine_concentration, &self.input)
+            })
+            .collect();
+
+        match self.input.format {
+            Format::Csv => {
+                writeln!(
+                    buffer,
+                    "seed,mine_concentration,start_us,generate_ns,lookup_ns,travel_ns,render_us,render_large_us,grid_bytes,travel_bytes",
+                )?;
+                for run in runs.iter() {
+                    writeln!(
+                        buffer,
+                        "{},{},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{},{}",
+                        run.seed, run.mine_concentration, run.start_us, run.generate_ns, run.lookup_ns,
+                        run.travel_ns, run.render_us, run.render_large_us, run.grid_bytes, run.travel_bytes,
+                    )?;
+                }
+                if !runs.is_empty() {
+                    let count = runs.len() as f64;
+                    let mean = |f: fn(&GridRun) -> f64| runs.iter().map(f).sum::<f64>() / count;
+                    eprintln!(
+                        "{} runs: {:.1} us to find the start, {:.1} ns per new cell, {:.1} ns per lookup, \
+                         {:.1} ns per far lookup, {:.1} us per frame, {:.1} us per 300x100 frame, \
+                         {:.0} grid bytes, {:.0} grid bytes after travelling",
+                        runs.len(),
+                        mean(|run| run.start_us), mean(|run| run.generate_ns), mean(|run| run.lookup_ns),
+                        mean(|run| run.travel_ns), mean(|run| run.render_us), mean(|run| run.render_large_us),
+                        mean(|run| run.grid_bytes as f64), mean(|run| run.travel_bytes as f64),
+                    );
+                }
+            },
+            Format::Json => {
+                serde_json::to_writer_pretty(&mut buffer, &runs)?;
+                writeln!(buffer)?;
+            },
+        }
+        buffer.flush()
+    }
+
+    fn seeds(&self) -> io::Result<Range<u64>> {
+        let Some(end) = self.input.first_seed.checked_add(self.input.seeds) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--first-seed plus --seeds goes past the largest seed",
+            ));
+        };
+        Ok(self.input.first_seed..end)
+    }
+
+    fn play(&self, seed: u64, mine_concentration: f64) -> Run {
+        let mut game = Game::new(
+            self.input.world_config(seed, mine_concentration), Game::HEADLESS_WINDOW_SIZE, false,
+            AutoPlay::new(false, 0, false), None,
+        );
+        let start_instant = time::Instant::now();
+        let mut last_move = PlaceI32::ORIGIN;
+        let mut moves = 0;
+        let mut guesses = 0;
+        let outcome = loop {
+            if let GameState::Lost = game.state { break Outcome::Lost; }
+            if moves >= self.input.max_moves { break Outcome::MoveLimit; }
+
+            let solver = Solver::new(&game.grid, mine_concentration);
+            let hint = match solver.hint(last_move, Game::HINT_RADIUS) {
+                Some(Hint::Certain(finding)) => Some(Hint::Certain(finding)),
+                // there may still be a certain move elsewhere on the frontier
+                hint => Self::certain_anywhere(&game, &solver).map(Hint::Certain).or(hint),
+            };
+            let Some(hint) = hint else {
+                break Outcome::Stuck;
+            };
+            match (hint, self.input.strategy) {
+                (Hint::Certain(finding), _) => {
+                    last_move = finding.place;
+                    match finding.verdict {
+                        Verdict::Safe => game.reveal(finding.place),
+                        Verdict::Mine => game.toggle_flag(finding.place),
+                    }
+                },
+                (Hint::Guess { .. }, Strategy::Logic) => break Outcome::Stuck,
+                (Hint::Guess { place, .. }, Strategy::Guess) => {
+                    last_move = place;
+                    guesses += 1;
+                    game.reveal(place);
+                },
+            }
+            moves += 1;
+        };
+
+        let seconds = start_instant.elapsed().as_secs_f64();
+        Run {
+            seed,
+            mine_concentration,
+            score: game.revealed_cell_count,
+            moves,
+            guesses,
+            outcome,
+            seconds,
+            cells_per_second: game.revealed_cell_count as f64 / seconds,
+            grid_bytes: game.grid.memory_usage(),
+        }
+    }
+
+    /// A certain move anywhere in the explored part of the grid, safe
+    /// cells first.
+    fn certain_anywhere(game: &Game, solver: &Solver) -> Option<Finding> {
+        let area = game.grid.bounding_box()?.grow(game.grid.topology().reach());
+        solver.analyse(area).findings.into_iter()
+            .min_by_key(|finding| finding.verdict == Verdict::Mine)
+    }
+
+    fn summarise(mine_concentration: f64, runs: &[Run]) -> Option<Summary> {
+        if runs.is_empty() { return None; }
+        let mut scores: Vec<u32> = runs.iter().map(|run| run.score).collect();
+        scores.sort_unstable();
+        let percentile = |fraction: f64| {
+            scores[((scores.len() - 1) as f64 * fraction).round() as usize]
+        };
+        let count = runs.len() as f64;
+        let seconds: f64 = runs.iter().map(|run| run.seconds).sum();
+        Some(Summary {
+            mine_concentration,
+            runs: runs.len(),
+            lost: runs.iter().filter(|run| matches!(run.outcome, Outcome::Lost)).count(),
+            score_min:    percentile(0f64),
+            score_p25:    percentile(0.25f64),
+            score_median: percentile(0.5f64),
+            score_p75:    percentile(0.75f64),
+            score_max:    percentile(1f64),
+            score_mean:   scores.iter().map(|&score| score as f64).sum::<f64>() / count,
+            guesses_mean: runs.iter().map(|run| run.guesses as f64).sum::<f64>() / count,
+            cells_per_second: scores.iter().map(|&score| score as f64).sum::<f64>() / seconds,
+            grid_bytes_max: runs.iter().map(|run| run.grid_bytes).max().unwrap_or(0),
+        })
+    }
+
+    fn write_csv(buffer: &mut impl io::Write, runs: &[Run]) -> io::Result<()> {
+        writeln!(
+            buffer,
+            "seed,mine_concentration,score,moves,guesses,outcome,seconds,cells_per_second,grid_bytes",
+        )?;
+        for run in runs {
+            let outcome = match run.outcome {
+                Outcome::Lost      => "lost",
+                Outcome::Stuck     => "stuck",
+                Outcome::MoveLimit => "move_limit",
+            };
+            writeln!(
+                buffer,
+                "{},{},{},{},{},{},{:.6},{:.1},{}",
+                run.seed, run.mine_concentration, run.score, run.moves, run.guesses,
+                outcome, run.seconds, run.cells_per_second, run.grid_bytes,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write_summaries(buffer: &mut impl io::Write, summaries: &[Summary]) -> io::Result<()> {
+        for summary in summaries {
+            writeln!(
+                buffer,
+                "concentration {}: {} runs, {} lost, score min {} p25 {} median {} p75 {} max {} mean {:.1}, \
+                 {:.2} guesses per run, {:.0} cells/s, up to {} grid bytes",
+                summary.mine_concentration, summary.runs, summary.lost,
+                summary.score_min, summary.score_p25, summary.score_median,
+                summary.score_p75, summary.score_max, summary.score_mean,
+                summary.guesses_mean, summary.cells_per_second, summary.grid_bytes_max,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use crate::game::{Action, Direction, Game};
+use crate::game::auto_play::AutoPlay;
+use crate::game::input::BenchInput;
+use crate::grid::Grid;
+use crate::grid::cell::CellValue;
+use crate::grid::cell_builder::CellBuilder;
+use crate::helper::{PlaceI32, RectI32, SizeI32, SizeUsize};
+use crate::view::View;
+use serde::Serialize;
+use std::hint::black_box;
+use std::{io, time};
+
+/// Times the grid on its own, to compare ways of storing cells.
+#[derive(Debug, Serialize)]
+pub struct GridRun {
+    pub seed: u64,
+    pub mine_concentration: f64,
+    /// microseconds spent finding a start without mines around it
+    pub start_us: f64,
+    /// nanoseconds per lookup of a cell that has to be generated
+    pub generate_ns: f64,
+    /// nanoseconds per lookup of a cell that is stored
+    pub lookup_ns: f64,
+    /// nanoseconds per lookup while travelling far from the start
+    pub travel_ns: f64,
+    /// microseconds per frame drawn, with every empty cell revealed
+    pub render_us: f64,
+    /// microseconds per frame drawn on a 300x100 terminal
+    pub render_large_us: f64,
+    /// memory held by the grid after every cell of the square around the start was flagged
+    pub grid_bytes: usize,
+    /// memory held by the grid after travelling
+    pub travel_bytes: usize,
+}
+
+impl GridRun {
+    /// Cells from the start to each side of the square that is looked at.
+    const RADIUS: i32 = 256;
+    const LOOKUP_PASSES: u32 = 10;
+    const TRAVEL_DISTANCE: i32 = 1 << 20;
+    const TRAVEL_STEP: i32 = 61;
+    const FRAMES: u32 = 200;
+    const LARGE_WINDOW_SIZE: SizeUsize = SizeUsize { width: 300, height: 100 };
+
+    /// Times the world `input` describes, at `seed` and `mine_concentration`.
+    pub fn new(seed: u64, mine_concentration: f64, input: &BenchInput) -> GridRun {
+        let mut cell_builder = None;
+        let start_us = Self::time_ns(1f64, || {
+            cell_builder = Some(CellBuilder::new(
+                mine_concentration, Some(seed), &input.generator, input.generator_version, None, input.topology,
+                |message: &'static str| panic!("{}", message),
+            ));
+        }) / 1000f64;
+        let cell_builder = cell_builder.expect("the start was just found");
+        let area = RectI32::around(PlaceI32::ORIGIN, Self::RADIUS);
+        let cell_count = area.size.width as f64 * area.size.height as f64;
+
+        let mut grid = Grid::new(cell_builder.clone());
+        let generate_ns = Self::time_ns(cell_count, || {
+            for place in area.places() {
+                black_box(grid.peek(place));
+            }
+        });
+        for place in area.places() {
+            grid.get_mut(place).flag();
+        }
+        let lookup_ns = Self::time_ns(cell_count * Self::LOOKUP_PASSES as f64, || {
+            for _ in 0..Self::LOOKUP_PASSES {
+                for place in area.places() {
+                    black_box(grid.peek(place));
+                }
+            }
+        });
+        let grid_bytes = grid.memory_usage();
+
+        let grid = Grid::new(cell_builder);
+        let steps = Self::TRAVEL_DISTANCE / Self::TRAVEL_STEP;
+        let travel_ns = Self::time_ns(steps as f64 * 2f64, || {
+            for step in 0..steps {
+                let distance = step * Self::TRAVEL_STEP;
+                black_box(grid.peek(PlaceI32 { x: distance,  y: distance / 3 }));
+                black_box(grid.peek(PlaceI32 { x: -distance, y: -distance / 5 }));
+            }
+        });
+        let travel_bytes = grid.memory_usage();
+
+        GridRun {
+            seed,
+            mine_concentration,
+            start_us,
+            generate_ns,
+            lookup_ns,
+            travel_ns,
+            render_us: Self::render_us(seed, mine_concentration, input, Game::HEADLESS_WINDOW_SIZE),
+            render_large_us: Self::render_us(seed, mine_concentration, input, Self::LARGE_WINDOW_SIZE),
+            grid_bytes,
+            travel_bytes,
+        }
+    }
+
+    fn render_us(seed: u64, mine_concentration: f64, input: &BenchInput, window_size: SizeUsize) -> f64 {
+        let mut game = Game::new(
+            input.world_config(seed, mine_concentration), window_size, false,
+            AutoPlay::new(false, 0, false), None,
+        );
+        // numbers are what is most costly to draw, so show as many as possible
+        let matrix_size = View::matrix_size(window_size);
+        let area = RectI32 {
+            corner: PlaceI32 { x: -(matrix_size.width as i32), y: -(matrix_size.height as i32) },
+            size: SizeI32 {
+                width:  matrix_size.width  as i32 * 2 + Self::FRAMES as i32,
+                height: matrix_size.height as i32 * 2,
+            },
+        };
+        for place in area.places() {
+            let mut cell = game.grid.get_mut(place);
+            if let CellValue::Empty = cell.value {
+                cell.reveal();
+            }
+        }
+        Self::time_ns(Self::FRAMES as f64, || {
+            for _ in 0..Self::FRAMES {
+                // scroll, so that frames also look at new cells
+                game.action(Action::MoveCursor(Direction::Right));
+                game.view().render(&mut io::sink()).expect("writing to a sink does not fail");
+            }
+        }) / 1000f64
+    }
+
+    fn time_ns(count: f64, f: impl FnOnce()) -> f64 {
+        let start_instant = time::Instant::now();
+        f();
+        start_instant.elapsed().as_nanos() as f64 / count
+    }
+}
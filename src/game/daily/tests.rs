@@ -0,0 +1,40 @@
+use super::Date;
+
+fn date(text: &str) -> Date {
+    text.parse().unwrap()
+}
+
+#[test]
+fn days_count_from_the_epoch() {
+    for (text, days) in [
+        ("1970-01-01", 0),
+        ("1969-12-31", -1),
+        ("1970-03-01", 59),
+        ("2000-02-29", 11016),
+        ("2024-02-29", 19782),
+        ("2026-10-31", 20757),
+        ("2026-11-01", 20758),
+        ("1600-03-01", -135080),
+    ] {
+        assert_eq!(date(text).days(), days, "{text}");
+        assert_eq!(Date::from_days(days), date(text), "{text}");
+    }
+}
+
+#[test]
+fn days_round_trip() {
+    // four centuries either side of the epoch cover every leap year rule
+    for days in -146097..=146097 {
+        assert_eq!(Date::from_days(days).days(), days);
+    }
+}
+
+#[test]
+fn only_leap_years_have_february_29th() {
+    for text in ["2000-02-29", "2024-02-29", "1600-02-29"] {
+        assert!(text.parse::<Date>().is_ok(), "{text}");
+    }
+    for text in ["1900-02-29", "2026-02-29", "2100-02-29", "2026-02-30", "2026-13-01", "2026-11-00"] {
+        assert!(text.parse::<Date>().is_err(), "{text}");
+    }
+}
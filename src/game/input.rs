@@ -1,4 +1,13 @@
-use clap::Parser;
+use crate::game::WorldConfig;
+use crate::game::daily::Date;
+use crate::game::multiplayer::MineRule;
+use crate::game::world_code::{self, WorldCode};
+use crate::grid::density::DensityCurve;
+use crate::grid::generator::{self, GeneratorKind};
+use crate::grid::topology::Topology;
+use crate::helper::{PlaceI32, RectI32, SizeI32};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 
 /// minesweeper on an infinite grid in the terminal
 #[derive(Parser, Debug)]
@@ -8,26 +17,313 @@ use clap::Parser;
     long_about = r#"
 minesweeper on an infinite grid in the terminal
 keybinds:
-    arrow keys for movement
+    arrow keys for movement; on hexagons up and down zigzag
+    y, u, b and n to move up left, up right, down left and down right;
+        on hexagons these step to the neighbours above and below
     space to reveal
     f to flag
     a to reveal adjacent, non-flagged cells
-    r to restart"#,
+    h to show a hint
+    s to save the game
+    e to export the explored world as png and svg
+    p to cycle the mine probability overlay
+    o to start or pause auto-play
+    , and . to slow down or speed up auto-play
+    v to follow the player when spectating
+    r to restart
+    Esc or Ctrl-C to quit"#,
 )]
 pub struct Input {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// fraction of cells that are mines
-    #[arg(name = "mine-concentration", short, long, default_value_t = Self::DEFAULT_MINE_CONCENTRATION)]
+    #[arg(name = "mine-concentration", short, long, conflicts_with = "daily", default_value_t = Self::DEFAULT_MINE_CONCENTRATION)]
     pub mine_concentration: f64,
     
-    /// seed for the world generator (defaults to random)
-    #[arg(short, long, hide_default_value = true)]
+    /// seed for the world generator, a number or any text (defaults to random)
+    #[arg(short, long, value_parser = world_code::parse_seed, conflicts_with = "daily")]
     pub seed: Option<u64>,
 
+    /// where mines go: uniform, clustered, noise, stratified, or a file with a layout (* for mines)
+    #[arg(long, value_parser = generator::parse_generator, default_value = "uniform", conflicts_with_all = ["daily", "world", "load"])]
+    pub generator: GeneratorKind,
+
+    /// version of world generation, to play a seed as older versions made it
+    #[arg(name = "generator-version", long, value_parser = parse_version, default_value_t = GeneratorKind::VERSION, conflicts_with_all = ["daily", "world", "load"])]
+    pub generator_version: u8,
+
+    /// mine concentration far from the start; it rises or falls steadily from --mine-concentration until --density-radius
+    #[arg(name = "far-mine-concentration", long, conflicts_with_all = ["daily", "world", "load"])]
+    pub far_mine_concentration: Option<f64>,
+
+    /// cells from the start at which --far-mine-concentration is reached
+    #[arg(name = "density-radius", long, value_parser = parse_radius, default_value_t = Self::DEFAULT_DENSITY_RADIUS, requires = "far-mine-concentration")]
+    pub density_radius: f64,
+
+    /// shape of the cells and which neighbours numbers count
+    #[arg(long, value_enum, default_value_t = Topology::Square, conflicts_with_all = ["daily", "world", "load"])]
+    pub topology: Topology,
+
+    /// continue a game saved with s
+    #[arg(long, conflicts_with_all = ["seed", "mine-concentration", "daily", "world", "host", "join", "publish", "spectate"])]
+    pub load: Option<PathBuf>,
+
+    /// play the world of a code shown on the game over screen
+    #[arg(short, long, conflicts_with_all = ["seed", "mine-concentration", "daily"])]
+    pub world: Option<WorldCode>,
+
+    /// play the daily challenge of today, or of an earlier date (YYYY-MM-DD)
+    #[arg(long, num_args = 0..=1, value_name = "DATE")]
+    pub daily: Option<Option<Date>>,
+
     /// use gray background
     #[arg(name = "light-mode", short, long, default_value_t = false)]
     pub light_mode: bool,
+
+    /// let the solver play from the start
+    #[arg(name = "auto-play", long, default_value_t = false)]
+    pub auto_play: bool,
+
+    /// auto-play steps per second (0 for as fast as possible)
+    #[arg(name = "auto-play-speed", long, default_value_t = Self::DEFAULT_AUTO_PLAY_SPEED)]
+    pub auto_play_speed: u32,
+
+    /// let auto-play guess when no move is certain
+    #[arg(name = "auto-play-guess", long, default_value_t = false)]
+    pub auto_play_guess: bool,
+
+    /// play through JSON lines on stdin and stdout instead of the terminal
+    #[arg(long, default_value_t = false, conflicts_with_all = ["daily", "world", "load", "host", "join", "publish", "spectate"])]
+    pub protocol: bool,
+
+    /// host a shared world for other players at an address like 127.0.0.1:7878
+    #[arg(long, conflicts_with_all = ["join", "publish", "spectate"])]
+    pub host: Option<String>,
+
+    /// join the world hosted at an address
+    #[arg(long, conflicts_with_all = ["publish", "spectate"])]
+    pub join: Option<String>,
+
+    /// let others watch this game from an address
+    #[arg(long, conflicts_with = "spectate")]
+    pub publish: Option<String>,
+
+    /// watch the game published at an address
+    #[arg(long)]
+    pub spectate: Option<String>,
+
+    /// what happens when a player reveals a mine in a shared world
+    #[arg(name = "mine-rule", long, value_enum, default_value_t = MineRule::EndGame)]
+    pub mine_rule: MineRule,
+
+    /// when hosting, let every player race on their own copy of the world
+    #[arg(long, default_value_t = false, requires = "host")]
+    pub race: bool,
+
+    /// seconds each player gets in a race
+    #[arg(name = "time-limit", long, requires = "race")]
+    pub time_limit: Option<u64>,
+}
+
+impl BenchInput {
+    /// The world a run plays at `seed` and `mine_concentration`.
+    pub fn world_config(&self, seed: u64, mine_concentration: f64) -> WorldConfig {
+        WorldConfig {
+            mine_concentration,
+            seed: Some(seed),
+            generator: self.generator.clone(),
+            version: self.generator_version,
+            density_curve: None,
+            topology: self.topology,
+        }
+    }
 }
 
 impl Input {
     pub const DEFAULT_MINE_CONCENTRATION: f64 = 0.2f64;
-}
\ No newline at end of file
+    pub const DEFAULT_AUTO_PLAY_SPEED: u32 = 8;
+    pub const DEFAULT_DENSITY_RADIUS: f64 = 2000f64;
+
+    /// The world chosen by the options that describe one.
+    pub fn world_config(&self) -> WorldConfig {
+        WorldConfig {
+            mine_concentration: self.mine_concentration,
+            seed: self.seed,
+            generator: self.generator.clone(),
+            version: self.generator_version,
+            density_curve: self.far_mine_concentration
+                .map(|far| DensityCurve { far, radius: self.density_radius }),
+            topology: self.topology,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// play many games with the solver, without a terminal
+    Bench(BenchInput),
+    /// show the best games
+    Leaderboard(LeaderboardInput),
+    /// draw a saved game as a png or svg image
+    Export(ExportInput),
+    /// print part of a world as text
+    Render(RenderInput),
+}
+
+#[derive(Args, Debug)]
+pub struct RenderInput {
+    /// seed for the world generator, a number or any text
+    #[arg(short, long, value_parser = world_code::parse_seed)]
+    pub seed: u64,
+
+    /// fraction of cells that are mines
+    #[arg(name = "mine-concentration", short, long, default_value_t = Input::DEFAULT_MINE_CONCENTRATION)]
+    pub mine_concentration: f64,
+
+    /// where mines go: uniform, clustered, noise, stratified, or a file with a layout (* for mines)
+    #[arg(long, value_parser = generator::parse_generator, default_value = "uniform")]
+    pub generator: GeneratorKind,
+
+    /// version of world generation, to print a seed as older versions made it
+    #[arg(name = "generator-version", long, value_parser = parse_version, default_value_t = GeneratorKind::VERSION)]
+    pub generator_version: u8,
+
+    /// shape of the cells and which neighbours numbers count
+    #[arg(long, value_enum, default_value_t = Topology::Square)]
+    pub topology: Topology,
+
+    /// cells to print as x,y,width,height from the bottom left (defaults to -20,-10,40,21)
+    #[arg(short, long, value_parser = parse_area, allow_hyphen_values = true)]
+    pub area: Option<RectI32>,
+
+    /// show every cell instead of the opening of a new game
+    #[arg(short, long, default_value_t = false)]
+    pub reveal: bool,
+
+    /// plain text, or coloured with ansi escape codes
+    #[arg(short, long, value_enum, default_value_t = RenderFormat::Plain)]
+    pub format: RenderFormat,
+
+    /// use gray background
+    #[arg(name = "light-mode", short, long, default_value_t = false)]
+    pub light_mode: bool,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum RenderFormat {
+    Plain,
+    Ansi,
+}
+
+#[derive(Args, Debug)]
+pub struct ExportInput {
+    /// game saved with s
+    pub save: PathBuf,
+
+    /// image to write; its extension picks the format (defaults to the save as png)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// cells to draw as x,y,width,height from the bottom left (defaults to everything explored)
+    #[arg(short, long, value_parser = parse_area, allow_hyphen_values = true)]
+    pub area: Option<RectI32>,
+
+    /// use gray background
+    #[arg(name = "light-mode", short, long, default_value_t = false)]
+    pub light_mode: bool,
+}
+
+fn parse_area(text: &str) -> Result<RectI32, String> {
+    let numbers: Vec<i32> = text.split(',')
+        .map(|number| number.trim().parse().map_err(|_| format!("invalid number `{number}`")))
+        .collect::<Result<_, _>>()?;
+    let [x, y, width, height] = numbers[..] else {
+        return Err("expected x,y,width,height".to_string());
+    };
+    if width <= 0 || height <= 0 {
+        return Err("width and height must be positive".to_string());
+    }
+    Ok(RectI32 { corner: PlaceI32 { x, y }, size: SizeI32 { width, height } })
+}
+
+fn parse_version(text: &str) -> Result<u8, String> {
+    match text.parse() {
+        Ok(version) if GeneratorKind::check_version(version).is_ok() => Ok(version),
+        _ => Err(format!("expected a version from 0 to {}", GeneratorKind::VERSION)),
+    }
+}
+
+fn parse_radius(text: &str) -> Result<f64, String> {
+    match text.parse() {
+        Ok(radius) if radius > 0f64 => Ok(radius),
+        _ => Err("expected a positive number of cells".to_string()),
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct LeaderboardInput {
+    /// show the daily challenge of today, or of an earlier date (YYYY-MM-DD)
+    #[arg(long, num_args = 0..=1, value_name = "DATE")]
+    pub daily: Option<Option<Date>>,
+}
+
+#[derive(Args, Debug)]
+pub struct BenchInput {
+    /// number of seeds to play at each mine concentration
+    #[arg(short = 'n', long, default_value_t = 100)]
+    pub seeds: u64,
+
+    /// first seed; the rest follow consecutively
+    #[arg(name = "first-seed", long, default_value_t = 0)]
+    pub first_seed: u64,
+
+    /// fractions of cells that are mines, separated by commas
+    #[arg(
+        name = "mine-concentrations", short, long,
+        value_delimiter = ',', default_values_t = [Input::DEFAULT_MINE_CONCENTRATION],
+    )]
+    pub mine_concentrations: Vec<f64>,
+
+    /// where mines go: uniform, clustered, noise or stratified
+    #[arg(long, value_parser = generator::parse_generator, default_value = "uniform")]
+    pub generator: GeneratorKind,
+
+    /// version of world generation
+    #[arg(name = "generator-version", long, value_parser = parse_version, default_value_t = GeneratorKind::VERSION)]
+    pub generator_version: u8,
+
+    /// shape of the cells and which neighbours numbers count
+    #[arg(long, value_enum, default_value_t = Topology::Square)]
+    pub topology: Topology,
+
+    /// how the solver picks moves
+    #[arg(long, value_enum, default_value_t = Strategy::Logic)]
+    pub strategy: Strategy,
+
+    /// reveals and flags before a game is stopped
+    #[arg(name = "max-moves", long, default_value_t = 10000)]
+    pub max_moves: u32,
+
+    /// output format; csv lists the games and writes the summary to stderr
+    #[arg(short, long, value_enum, default_value_t = Format::Csv)]
+    pub format: Format,
+
+    /// time grid lookups, rendering and memory instead of playing
+    #[arg(long, default_value_t = false)]
+    pub grid: bool,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Strategy {
+    /// only make certain moves; stop when none are left
+    Logic,
+    /// make the least risky guess when no move is certain
+    Guess,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    Csv,
+    Json,
+}
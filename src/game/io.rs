@@ -1,7 +1,8 @@
 use crate::game::{Game, Action, Direction::*};
+use crate::game::multiplayer::NetworkEvent;
 use crate::helper::SizeUsize;
-use std::sync::mpsc::{Receiver, Sender};
-use std::{io, thread, sync::mpsc};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::{io, thread, time, sync::mpsc};
 use crossterm::event::KeyModifiers;
 use crossterm::terminal::{disable_raw_mode, Clear, ClearType};
 use crossterm::{
@@ -27,6 +28,8 @@ use crossterm::{
 pub enum IoEvent {
     CrosstermEvent(crossterm::event::Event),
     Second,
+    Step,
+    Network(NetworkEvent),
     Panic(&'static str),
 }
 
@@ -36,13 +39,20 @@ pub struct Io<'a> {
     window_size: SizeUsize,
     rx: Receiver<IoEvent>,
     tx:   Sender<IoEvent>,
+    next_step: time::Instant,
 }
 
 impl<'a> Io<'a> {
-    pub fn new(game: &mut Game, window_size: SizeUsize) -> Io {
+    /// Longest time auto-play runs at full speed without rendering.
+    const FRAME: time::Duration = time::Duration::from_millis(16);
+
+    pub fn new(game: &mut Game, window_size: SizeUsize) -> Io<'_> {
         let (tx, rx) = mpsc::channel();
         game.tx_panic = Some(tx.clone());
-        Io { game, window_size, rx, tx }
+        if let Some(multiplayer) = &mut game.multiplayer {
+            multiplayer.start(tx.clone());
+        }
+        Io { game, window_size, rx, tx, next_step: time::Instant::now() }
     }
 
     pub fn run(&mut self, mut buffer: impl io::Write) -> io::Result<()> {
@@ -68,12 +78,28 @@ impl<'a> Io<'a> {
             let view = self.game.view();
             view.render(&mut buffer)?;
             buffer.flush()?;
-            match self.rx.recv().expect("failed to receive io event") {
+            let event = if self.game.auto_play_interval().is_some() {
+                let timeout = self.next_step.saturating_duration_since(time::Instant::now());
+                match self.rx.recv_timeout(timeout) {
+                    Ok(event) => event,
+                    Err(RecvTimeoutError::Timeout) => IoEvent::Step,
+                    Err(RecvTimeoutError::Disconnected) => panic!("failed to receive io event"),
+                }
+            } else {
+                self.rx.recv().expect("failed to receive io event")
+            };
+            match event {
                 IoEvent::CrosstermEvent(event) => {
                     match event {
                         TerminalEvent::Key(KeyEvent {
                             code: KeyCode::Char('c'), modifiers, ..
                         }) if modifiers.contains(KeyModifiers::CONTROL) => {
+                            self.game.quit();
+                            Self::quit(buffer)?;
+                            return Ok(());
+                        },
+                        TerminalEvent::Key(KeyEvent { code: KeyCode::Esc, .. }) => {
+                            self.game.quit();
                             Self::quit(buffer)?;
                             return Ok(());
                         },
@@ -92,7 +118,9 @@ impl<'a> Io<'a> {
                         _ => (),
                     }
                 },
-                IoEvent::Second => (), // update display when timer increments
+                IoEvent::Second => self.game.second(), // also update display when timer increments
+                IoEvent::Step => self.auto_play(),
+                IoEvent::Network(event) => self.game.network(event),
                 IoEvent::Panic(message) => {
                     Self::quit(buffer)?;
                     eprintln!("{}", message);
@@ -102,6 +130,20 @@ impl<'a> Io<'a> {
         }
     }
 
+    fn auto_play(&mut self) {
+        let frame_end = time::Instant::now() + Self::FRAME;
+        loop {
+            self.game.action(Action::AutoPlayStep);
+            let Some(interval) = self.game.auto_play_interval() else { return; };
+            let now = time::Instant::now();
+            if interval.is_zero() && now < frame_end {
+                continue;
+            }
+            self.next_step = now + interval;
+            return;
+        }
+    }
+
     fn quit(mut buffer: impl io::Write) -> io::Result<()> {
         buffer.execute(LeaveAlternateScreen)?;
         disable_raw_mode()?;
@@ -119,10 +161,22 @@ impl<'a> Io<'a> {
             KeyCode::Right     => Action::MoveCursor(Right),
             KeyCode::Down      => Action::MoveCursor(Down),
             KeyCode::Up        => Action::MoveCursor(Up),
+            KeyCode::Char('y') => Action::MoveCursor(UpLeft),
+            KeyCode::Char('u') => Action::MoveCursor(UpRight),
+            KeyCode::Char('b') => Action::MoveCursor(DownLeft),
+            KeyCode::Char('n') => Action::MoveCursor(DownRight),
 
             KeyCode::Char(' ') => Action::Reveal,
             KeyCode::Char('a') => Action::RevealAdjacent,
             KeyCode::Char('f') => Action::Flag,
+            KeyCode::Char('h') => Action::Hint,
+            KeyCode::Char('s') => Action::Save,
+            KeyCode::Char('e') => Action::Export,
+            KeyCode::Char('p') => Action::ToggleHeatMap,
+            KeyCode::Char('o') => Action::ToggleAutoPlay,
+            KeyCode::Char(',') => Action::AutoPlaySlower,
+            KeyCode::Char('.') => Action::AutoPlayFaster,
+            KeyCode::Char('v') => Action::ToggleFollow,
 
             KeyCode::Char('r') => Action::Reset,
             _ => return,
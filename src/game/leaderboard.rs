@@ -0,0 +1,108 @@
+use crate::game::daily::Date;
+use serde::{Deserialize, Serialize};
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+use std::{env, fs};
+
+/// A finished game.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Entry {
+    pub score: u32,
+    /// seconds played
+    pub time: u64,
+    pub hints_used: u32,
+    pub seed: u64,
+    pub mine_concentration: f64,
+    /// version of world generation; games recorded before there were
+    /// versions are version 0
+    #[serde(default)]
+    pub version: u8,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DailyEntry {
+    pub date: Date,
+    #[serde(flatten)]
+    pub entry: Entry,
+}
+
+/// Best results, kept in a JSON file in the user's data directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub games: Vec<Entry>,
+    pub daily: Vec<DailyEntry>,
+}
+
+impl Leaderboard {
+    /// Entries kept in the games section, and for each day in the daily section.
+    pub const MAX_ENTRIES: usize = 100;
+
+    pub fn path() -> PathBuf {
+        let data_dir = env::var_os("XDG_DATA_HOME").map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+            .unwrap_or_default();
+        data_dir.join("minesweeper").join("leaderboard.json")
+    }
+
+    pub fn load() -> io::Result<Leaderboard> {
+        match fs::read_to_string(Self::path()) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Leaderboard::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// Adds a game to the file, in the daily section if it was a daily challenge.
+    pub fn record(entry: Entry, daily: Option<Date>) -> io::Result<()> {
+        let mut leaderboard = Self::load()?;
+        match daily {
+            Some(date) => {
+                leaderboard.daily.push(DailyEntry { date, entry });
+                leaderboard.daily.sort_by(|a, b| b.date.cmp(&a.date).then(b.entry.score.cmp(&a.entry.score)));
+                let mut kept = 0;
+                leaderboard.daily.retain(|daily| {
+                    if daily.date != date { return true; }
+                    kept += 1;
+                    kept <= Self::MAX_ENTRIES
+                });
+            },
+            None => {
+                leaderboard.games.push(entry);
+                leaderboard.games.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+                leaderboard.games.truncate(Self::MAX_ENTRIES);
+            },
+        }
+        leaderboard.save()
+    }
+
+    /// Prints the best games, or the results of one day's challenge.
+    pub fn print(&self, mut buffer: impl io::Write, daily: Option<Date>) -> io::Result<()> {
+        writeln!(
+            buffer, "{:>5} {:>7} {:>6} {:>5}  {:<20} {:<13} version",
+            "rank", "score", "time", "hints", "seed", "concentration",
+        )?;
+        let entries: Vec<&Entry> = match daily {
+            Some(date) => self.daily.iter()
+                .filter(|daily| daily.date == date)
+                .map(|daily| &daily.entry)
+                .collect(),
+            None => self.games.iter().collect(),
+        };
+        for (rank, entry) in entries.into_iter().enumerate() {
+            writeln!(
+                buffer, "{:>5} {:>7} {:>5}s {:>5}  {:<20} {:<13} {}",
+                rank + 1, entry.score, entry.time, entry.hints_used,
+                entry.seed, entry.mine_concentration, entry.version,
+            )?;
+        }
+        Ok(())
+    }
+}
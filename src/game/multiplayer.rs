@@ -0,0 +1,613 @@
+use crate::game::{Action, Game, GameState, WorldConfig};
+use crate::game::io::IoEvent;
+use crate::game::save::Save;
+use crate::grid::Grid;
+use crate::grid::cell::CellState;
+use crate::grid::density::DensityCurve;
+use crate::grid::generator::GeneratorKind;
+use crate::grid::topology::Topology;
+use crate::helper::PlaceI32;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Sender, SyncSender};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests;
+
+/// What happens when a player reveals a mine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum MineRule {
+    /// the game ends for everyone
+    EndGame,
+    /// only that player is out; the game ends when everyone is
+    Eliminate,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Mode {
+    /// everyone plays on the host's grid
+    Coop { rule: MineRule },
+    /// everyone plays their own grid of the same world
+    Race {
+        /// seconds each player gets, counted from the start of their game
+        time_limit: Option<u64>,
+    },
+    /// one player, watched by everyone else
+    Publish,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Player {
+    pub id: u32,
+    pub cursor: PlaceI32,
+    pub score: u32,
+    /// revealed a mine
+    pub eliminated: bool,
+    /// ran out of time in a race
+    pub finished: bool,
+}
+
+impl Player {
+    fn new(id: u32) -> Player {
+        Player { id, cursor: PlaceI32::ORIGIN, score: 0, eliminated: false, finished: false }
+    }
+}
+
+/// How a player's own game stands in a race.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    pub score: u32,
+    pub lost: bool,
+    pub finished: bool,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct CellUpdate {
+    pub place: PlaceI32,
+    pub state: CellState,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteAction {
+    Reveal,
+    Flag,
+    Chord,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Act { action: RemoteAction, place: PlaceI32 },
+    Cursor { place: PlaceI32 },
+    Progress { progress: Progress },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HostMessage {
+    Welcome {
+        player: u32,
+        #[serde(flatten)]
+        world: HostWorld,
+        mode: Mode,
+    },
+    Cells { cells: Vec<CellUpdate> },
+    Players { players: Vec<Player> },
+    Status { state: GameState, score: u32 },
+    /// the host started again, in this world
+    Reset {
+        #[serde(flatten)]
+        world: HostWorld,
+    },
+}
+
+/// How the host's world is made, so players can make the same one.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HostWorld {
+    pub seed: u64,
+    pub mine_concentration: f64,
+    #[serde(default)]
+    pub generator: GeneratorKind,
+    /// hosts that don't send a version generate worlds as version 0
+    #[serde(default)]
+    pub version: u8,
+    #[serde(default)]
+    pub density_curve: Option<DensityCurve>,
+    #[serde(default)]
+    pub topology: Topology,
+}
+
+impl HostWorld {
+    pub fn of(game: &Game) -> HostWorld {
+        HostWorld {
+            seed: game.cell_builder.seed,
+            mine_concentration: game.mine_concentration,
+            generator: game.generator.clone(),
+            version: game.cell_builder.version,
+            density_curve: game.cell_builder.density_curve,
+            topology: game.cell_builder.topology,
+        }
+    }
+
+    pub fn config(self) -> WorldConfig {
+        WorldConfig {
+            mine_concentration: self.mine_concentration,
+            seed: Some(self.seed),
+            generator: self.generator,
+            version: self.version,
+            density_curve: self.density_curve,
+            topology: self.topology,
+        }
+    }
+
+    /// Checks that this version can make the world.
+    pub fn check(&self) -> Result<(), String> {
+        GeneratorKind::check_version(self.version)?;
+        self.generator.check(self.topology)
+    }
+}
+
+#[derive(Debug)]
+pub enum NetworkEvent {
+    Joined(TcpStream),
+    FromClient(u32, ClientMessage),
+    Left(u32),
+    FromHost(HostMessage),
+}
+
+#[derive(Debug)]
+struct Connection {
+    player: u32,
+    stream: TcpStream,
+    /// lines waiting for the writer thread, so a slow player can't hold up the game
+    outgoing: SyncSender<String>,
+}
+
+impl Connection {
+    /// Lines queued for a player before they are cut off as stalled.
+    const QUEUE_LENGTH: usize = 1024;
+
+    fn new(player: u32, stream: TcpStream) -> io::Result<Connection> {
+        let mut writer = stream.try_clone()?;
+        let (outgoing, queue) = mpsc::sync_channel::<String>(Self::QUEUE_LENGTH);
+        thread::spawn(move || {
+            for line in queue {
+                if writer.write_all(line.as_bytes()).is_err() { break; }
+            }
+        });
+        Ok(Connection { player, stream, outgoing })
+    }
+
+    fn send(&self, message: &HostMessage) {
+        let Ok(line) = Multiplayer::line(message) else { return; };
+        if self.outgoing.try_send(line).is_err() {
+            // the player's reader stops and reports them gone
+            let _ = self.stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Role {
+    Host {
+        listener: Option<TcpListener>,
+        connections: Vec<Connection>,
+        /// every cell change since the game started, for players who join late
+        next_player: u32,
+        /// players as last sent to the clients
+        shared_players: Vec<Player>,
+    },
+    Client {
+        reader: Option<BufReader<TcpStream>>,
+        stream: TcpStream,
+        last_cursor: PlaceI32,
+        last_progress: Option<Progress>,
+    },
+}
+
+/// Play with others over TCP. In co-op the host applies every action
+/// and sends the resulting cell changes to the other players; in a race
+/// everyone plays alone and only scores are shared.
+#[derive(Debug)]
+pub struct Multiplayer {
+    role: Role,
+    pub mode: Mode,
+    /// the local player's id; the host is always 0
+    pub player: u32,
+    pub players: Vec<Player>,
+    /// the local player's time is up
+    pub finished: bool,
+    /// a spectator's camera follows the player
+    pub following: bool,
+    tx: Option<Sender<IoEvent>>,
+}
+
+impl Multiplayer {
+    pub const HOST_PLAYER: u32 = 0;
+
+    pub fn host(address: impl ToSocketAddrs, mode: Mode) -> io::Result<Multiplayer> {
+        let listener = TcpListener::bind(address)?;
+        Ok(Multiplayer {
+            role: Role::Host {
+                listener: Some(listener),
+                connections: Vec::new(),
+                next_player: Self::HOST_PLAYER + 1,
+                shared_players: Vec::new(),
+            },
+            mode,
+            player: Self::HOST_PLAYER,
+            players: vec![Player::new(Self::HOST_PLAYER)],
+            finished: false,
+            following: false,
+            tx: None,
+        })
+    }
+
+    /// Address the host listens on, until it is started.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        match &self.role {
+            Role::Host { listener: Some(listener), .. } => listener.local_addr().ok(),
+            _ => None,
+        }
+    }
+
+    /// Connects to a host, returning how its world is made.
+    pub fn join(address: impl ToSocketAddrs) -> io::Result<(Multiplayer, HostWorld)> {
+        let stream = TcpStream::connect(address)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let HostMessage::Welcome { player, world, mode } =
+            serde_json::from_str(&line)? else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "expected welcome from host"));
+        };
+        world.check().map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let multiplayer = Multiplayer {
+            role: Role::Client {
+                reader: Some(reader),
+                stream,
+                last_cursor: PlaceI32::ORIGIN,
+                last_progress: None,
+            },
+            mode,
+            player,
+            players: Vec::new(),
+            finished: false,
+            following: true,
+            tx: None,
+        };
+        Ok((multiplayer, world))
+    }
+
+    /// Starts the threads that turn network traffic into io events.
+    pub fn start(&mut self, tx: Sender<IoEvent>) {
+        match &mut self.role {
+            Role::Host { listener, .. } => {
+                let listener = listener.take().expect("multiplayer started twice");
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for stream in listener.incoming().flatten() {
+                        tx.send(IoEvent::Network(NetworkEvent::Joined(stream)))
+                            .expect("failed to send io event to main thread");
+                    }
+                });
+            },
+            Role::Client { reader, .. } => {
+                let reader = reader.take().expect("multiplayer started twice");
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for line in reader.lines() {
+                        let Ok(line) = line else { break; };
+                        let Ok(message) = serde_json::from_str(&line) else { continue; };
+                        tx.send(IoEvent::Network(NetworkEvent::FromHost(message)))
+                            .expect("failed to send io event to main thread");
+                    }
+                    let _ = tx.send(IoEvent::Panic("lost connection to host"));
+                });
+            },
+        }
+        self.tx = Some(tx);
+    }
+
+    pub fn eliminated(&self) -> bool {
+        self.players.iter().any(|player| player.id == self.player && player.eliminated)
+    }
+
+    /// Players other than the local one.
+    pub fn others(&self) -> impl Iterator<Item = &Player> {
+        self.players.iter().filter(|player| player.id != self.player)
+    }
+
+    pub fn shares_grid(&self) -> bool {
+        matches!(self.mode, Mode::Coop { .. } | Mode::Publish)
+    }
+
+    /// Watching a published game, without playing.
+    pub fn spectating(&self) -> bool {
+        matches!((&self.role, self.mode), (Role::Client { .. }, Mode::Publish))
+    }
+
+    /// Number of other instances connected to this host.
+    pub fn connections(&self) -> usize {
+        match &self.role {
+            Role::Host { connections, .. } => connections.len(),
+            Role::Client { .. } => 0,
+        }
+    }
+
+    pub fn toggle_follow(&mut self, game: &mut Game) {
+        if !self.spectating() { return; }
+        self.following = !self.following;
+        self.follow(game);
+    }
+
+    fn follow(&self, game: &mut Game) {
+        if !self.following { return; }
+        let Some(player) = self.players.iter().find(|player| player.id == Self::HOST_PLAYER) else { return; };
+        game.cursor = player.cursor;
+        game.tether_origin();
+    }
+
+    /// The last survivor of a race, or the highest score once every
+    /// player is out or out of time.
+    pub fn winner(&self) -> Option<&Player> {
+        let Mode::Race { .. } = self.mode else { return None; };
+        if self.players.len() < 2 { return None; }
+        let mut survivors = self.players.iter().filter(|player| !player.eliminated);
+        if let (Some(survivor), None) = (survivors.next(), survivors.next()) {
+            return Some(survivor);
+        }
+        if self.players.iter().any(|player| !player.eliminated && !player.finished) {
+            return None;
+        }
+        self.players.iter().max_by_key(|player| player.score)
+    }
+
+    /// Handles actions that must not be applied to the local game directly.
+    /// Returns `true` if the action was consumed.
+    pub fn intercept(&mut self, action: &Action, cursor: PlaceI32, state: GameState) -> bool {
+        let board_action = match action {
+            Action::Reveal         => Some(RemoteAction::Reveal),
+            Action::Flag           => Some(RemoteAction::Flag),
+            Action::RevealAdjacent => Some(RemoteAction::Chord),
+            _ => None,
+        };
+        if self.spectating() {
+            if let Action::MoveCursor(_) = action {
+                self.following = false;
+            }
+            return board_action.is_some() ||
+                matches!(action, Action::Reset | Action::ToggleAutoPlay | Action::AutoPlayStep);
+        }
+        if board_action.is_some() && (self.eliminated() || self.finished) {
+            return true;
+        }
+        let mode = self.mode;
+        let Role::Client { stream, .. } = &mut self.role else { return false; };
+        match (action, board_action, mode) {
+            // only the host restarts the game
+            (Action::Reset, _, _) => true,
+            (_, Some(action), Mode::Coop { .. }) => {
+                if let GameState::Underway = state {
+                    let _ = Self::send(stream, &ClientMessage::Act { action, place: cursor });
+                }
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Shares what the last action by `player` changed.
+    pub fn sync(&mut self, game: &mut Game, player: u32, state_before: GameState, reset: bool) {
+        if let Mode::Race { time_limit: Some(time_limit) } = self.mode {
+            self.check_time_limit(game, Duration::from_secs(time_limit));
+        }
+        let progress = Progress {
+            score: game.revealed_cell_count,
+            lost: matches!(game.state, GameState::Lost),
+            finished: self.finished,
+        };
+
+        if let Role::Client { stream, last_cursor, last_progress, .. } = &mut self.role {
+            match self.mode {
+                Mode::Coop { .. } if game.cursor != *last_cursor => {
+                    *last_cursor = game.cursor;
+                    let _ = Self::send(stream, &ClientMessage::Cursor { place: game.cursor });
+                },
+                Mode::Race { .. } if *last_progress != Some(progress) => {
+                    *last_progress = Some(progress);
+                    let _ = Self::send(stream, &ClientMessage::Progress { progress });
+                },
+                _ => (),
+            }
+            return;
+        }
+
+        if reset {
+            for player in self.players.iter_mut() {
+                *player = Player::new(player.id);
+            }
+            self.broadcast(&HostMessage::Reset { world: HostWorld::of(game) });
+        }
+
+        if let Mode::Coop { rule } = self.mode {
+            self.apply_mine_rule(game, rule, player, state_before);
+        }
+        if self.shares_grid() {
+            let changes = game.take_changes();
+            if !changes.is_empty() {
+                let cells: Vec<CellUpdate> = changes.into_iter()
+                    .map(|place| CellUpdate { place, state: game.grid.peek_state(place) })
+                    .collect();
+                self.broadcast(&HostMessage::Cells { cells });
+            }
+        }
+
+        if let Some(host) = self.players.iter_mut().find(|player| player.id == Self::HOST_PLAYER) {
+            host.cursor = game.cursor;
+            host.score = game.revealed_cell_count;
+            if let Mode::Race { .. } = self.mode {
+                host.eliminated = progress.lost;
+                host.finished = progress.finished;
+            }
+        }
+        self.broadcast_players();
+        if self.shares_grid() {
+            self.broadcast(&HostMessage::Status { state: game.state, score: game.revealed_cell_count });
+        }
+    }
+
+    /// Stops the local game once its time in a race is up.
+    fn check_time_limit(&mut self, game: &mut Game, time_limit: Duration) {
+        if self.finished { return; }
+        let GameState::Underway = game.state else { return; };
+        if game.start_instant.elapsed() < time_limit { return; }
+        self.finished = true;
+        game.end_instant = Some(game.start_instant + time_limit);
+    }
+
+    pub fn handle(&mut self, game: &mut Game, event: NetworkEvent) {
+        match event {
+            NetworkEvent::Joined(stream) => self.joined(game, stream),
+            NetworkEvent::Left(id) => {
+                if let Role::Host { connections, .. } = &mut self.role {
+                    connections.retain(|connection| connection.player != id);
+                }
+                self.players.retain(|player| player.id != id);
+                let state = game.state;
+                self.sync(game, id, state, false);
+            },
+            NetworkEvent::FromClient(id, message) => {
+                let Some(index) = self.players.iter().position(|player| player.id == id) else { return; };
+                let state = game.state;
+                match message {
+                    ClientMessage::Cursor { place } => self.players[index].cursor = place,
+                    ClientMessage::Progress { progress } => {
+                        let player = &mut self.players[index];
+                        player.score      = progress.score;
+                        player.eliminated = progress.lost;
+                        player.finished   = progress.finished;
+                    },
+                    ClientMessage::Act { .. } if !self.shares_grid() => (),
+                    ClientMessage::Act { .. } if self.players[index].eliminated => (),
+                    ClientMessage::Act { .. } if matches!(state, GameState::Lost) => (),
+                    ClientMessage::Act { place, .. } if !Grid::within_limits(place) => (),
+                    ClientMessage::Act { action, place } => {
+                        game.invalidate_analysis();
+                        match action {
+                            RemoteAction::Reveal => game.reveal(place),
+                            RemoteAction::Flag   => game.toggle_flag(place),
+                            RemoteAction::Chord  => game.reveal_adjacent(place),
+                        }
+                    },
+                }
+                self.sync(game, id, state, false);
+            },
+            NetworkEvent::FromHost(message) => match message {
+                HostMessage::Welcome { .. } => (),
+                HostMessage::Cells { cells } => {
+                    game.invalidate_analysis();
+                    for CellUpdate { place, state } in cells {
+                        game.grid.get_mut(place).state = state;
+                    }
+                },
+                HostMessage::Players { players } => {
+                    self.players = players;
+                    self.follow(game);
+                },
+                HostMessage::Status { state, score } => {
+                    game.revealed_cell_count = score;
+                    if let (GameState::Underway, GameState::Lost) = (game.state, state) {
+                        game.lose();
+                    }
+                },
+                HostMessage::Reset { world } => {
+                    if world.check().is_err() {
+                        Game::send_panic(&game.tx_panic, "host switched to a world this version can't make");
+                        return;
+                    }
+                    game.reset_to(world);
+                    self.finished = false;
+                    let state = game.state;
+                    self.sync(game, self.player, state, false);
+                },
+            },
+        }
+    }
+
+    fn joined(&mut self, game: &mut Game, stream: TcpStream) {
+        let Role::Host { connections, next_player, .. } = &mut self.role else { return; };
+        let id = *next_player;
+        *next_player += 1;
+
+        let Ok(reader) = stream.try_clone() else { return; };
+        let Ok(connection) = Connection::new(id, stream) else { return; };
+        connection.send(&HostMessage::Welcome { player: id, world: HostWorld::of(game), mode: self.mode });
+        connection.send(&HostMessage::Cells { cells: Save::cells(&game.grid) });
+        // spectators are not in the player list, so it is not sent again
+        connection.send(&HostMessage::Players { players: self.players.clone() });
+        connections.push(connection);
+        if !matches!(self.mode, Mode::Publish) {
+            self.players.push(Player::new(id));
+        }
+
+        let tx = self.tx.clone().expect("multiplayer not started");
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else { break; };
+                let Ok(message) = serde_json::from_str(&line) else { continue; };
+                tx.send(IoEvent::Network(NetworkEvent::FromClient(id, message)))
+                    .expect("failed to send io event to main thread");
+            }
+            let _ = tx.send(IoEvent::Network(NetworkEvent::Left(id)));
+        });
+
+        let state = game.state;
+        self.sync(game, id, state, false);
+    }
+
+    fn apply_mine_rule(&mut self, game: &mut Game, rule: MineRule, player: u32, state_before: GameState) {
+        let (GameState::Underway, GameState::Lost) = (state_before, game.state) else { return; };
+        let MineRule::Eliminate = rule else { return; };
+
+        if let Some(player) = self.players.iter_mut().find(|other| other.id == player) {
+            player.eliminated = true;
+        }
+        if self.players.iter().any(|player| !player.eliminated) {
+            game.state = GameState::Underway;
+            game.end_instant = None;
+        }
+    }
+
+    fn broadcast_players(&mut self) {
+        let Role::Host { shared_players, .. } = &mut self.role else { return; };
+        if *shared_players == self.players { return; }
+        *shared_players = self.players.clone();
+        self.broadcast(&HostMessage::Players { players: self.players.clone() });
+    }
+
+    fn broadcast(&mut self, message: &HostMessage) {
+        let Role::Host { connections, .. } = &mut self.role else { return; };
+        // players whose connection fails are removed when their reader stops
+        for connection in connections.iter() {
+            connection.send(message);
+        }
+    }
+
+    fn send(stream: &mut TcpStream, message: &impl Serialize) -> io::Result<()> {
+        stream.write_all(Self::line(message)?.as_bytes())
+    }
+
+    fn line(message: &impl Serialize) -> io::Result<String> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+        Ok(line)
+    }
+}
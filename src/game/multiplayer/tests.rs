@@ -0,0 +1,149 @@
+use super::{ClientMessage, HostMessage, HostWorld, MineRule, Mode, Multiplayer, NetworkEvent, RemoteAction, Role};
+use crate::game::auto_play::AutoPlay;
+use crate::game::io::IoEvent;
+use crate::game::{Action, Game, WorldConfig};
+use crate::grid::cell::CellValue;
+use crate::grid::generator::{GeneratorKind, Layout};
+use crate::grid::topology::Topology;
+use crate::helper::PlaceI32;
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+const VERSION: u8 = 1;
+
+fn game(mine_concentration: f64, seed: Option<u64>, tx_panic: Option<Sender<IoEvent>>) -> Game {
+    let config = WorldConfig {
+        mine_concentration,
+        seed,
+        generator: GeneratorKind::Uniform,
+        version: VERSION,
+        density_curve: None,
+        topology: Topology::Square,
+    };
+    let mut game = Game::new(
+        config, Game::HEADLESS_WINDOW_SIZE, false,
+        AutoPlay::new(false, 0, false), tx_panic,
+    );
+    game.record_changes();
+    game
+}
+
+/// Hands the next network event to `game`, returning it first.
+fn next_event(game: &mut Game, rx: &Receiver<IoEvent>) -> String {
+    let IoEvent::Network(event) = rx.recv().unwrap() else { panic!("expected a network event"); };
+    let description = format!("{event:?}");
+    game.network(event);
+    description
+}
+
+/// Mines and states of the cells around the origin.
+fn cells(game: &Game) -> Vec<(bool, String)> {
+    (-20..=20).flat_map(|y| (-20..=20).map(move |x| PlaceI32 { x, y }))
+        .map(|place| {
+            let cell = game.grid.peek(place);
+            (matches!(cell.value, CellValue::Mine), format!("{:?}", cell.state))
+        })
+        .collect()
+}
+
+/// Starts hosting `host` in co-op on a free port, returning its address.
+fn host(mut host: Game) -> (Game, Receiver<IoEvent>, SocketAddr) {
+    let mut multiplayer = Multiplayer::host("127.0.0.1:0", Mode::Coop { rule: MineRule::EndGame }).unwrap();
+    let address = multiplayer.local_addr().unwrap();
+    let (host_tx, host_rx) = mpsc::channel();
+    multiplayer.start(host_tx);
+    host.multiplayer = Some(multiplayer);
+    (host, host_rx, address)
+}
+
+/// Joins `host`, returning the client once it has the host's cells and players.
+fn join(host: &mut Game, host_rx: &Receiver<IoEvent>, address: SocketAddr) -> (Game, Receiver<IoEvent>) {
+    let joining = thread::spawn(move || Multiplayer::join(address).unwrap());
+    assert!(next_event(host, host_rx).starts_with("Joined"));
+    let (mut multiplayer, world) = joining.join().unwrap();
+    let (client_tx, client_rx) = mpsc::channel();
+    let mut client = game(world.mine_concentration, Some(world.seed), Some(client_tx.clone()));
+    multiplayer.start(client_tx);
+    client.multiplayer = Some(multiplayer);
+    while !next_event(&mut client, &client_rx).starts_with("FromHost(Players") {}
+    (client, client_rx)
+}
+
+#[test]
+fn a_reset_host_and_client_share_a_world() {
+    // no seed, so every reset picks a new world
+    let (mut host, host_rx, address) = host(game(0.2, None, None));
+    let (mut client, client_rx) = join(&mut host, &host_rx, address);
+
+    for _ in 0..3 {
+        let seed = host.cell_builder.seed;
+        host.action(Action::Reset);
+        while !next_event(&mut client, &client_rx).starts_with("FromHost(Reset") {}
+        // the host follows a reset with the cells it revealed and the players
+        while !next_event(&mut client, &client_rx).starts_with("FromHost(Status") {}
+        assert_ne!(host.cell_builder.seed, seed);
+        assert_eq!(client.cell_builder.seed, host.cell_builder.seed);
+        assert_eq!(cells(&client), cells(&host));
+    }
+}
+
+#[test]
+fn hosts_with_newer_worlds_are_refused() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let host = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let world = HostWorld {
+            version: GeneratorKind::VERSION + 1,
+            ..HostWorld::of(&game(0.2, Some(1), None))
+        };
+        let welcome = HostMessage::Welcome { player: 1, world, mode: Mode::Coop { rule: MineRule::EndGame } };
+        Multiplayer::send(&mut stream, &welcome).unwrap();
+    });
+    let error = Multiplayer::join(address).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    host.join().unwrap();
+}
+
+#[test]
+fn late_players_get_the_cells_changed_so_far() {
+    let (mut host, host_rx, address) = host(game(0.2, Some(1), None));
+    let flagged = PlaceI32 { x: 30, y: 30 };
+    host.toggle_flag(flagged);
+    host.toggle_flag(PlaceI32 { x: 31, y: 30 });
+    host.toggle_flag(PlaceI32 { x: 31, y: 30 });
+    let (client, _client_rx) = join(&mut host, &host_rx, address);
+    assert_eq!(cells(&client), cells(&host));
+    assert_eq!(client.grid.cells().count(), host.grid.cells().count());
+}
+
+#[test]
+fn actions_out_of_range_are_ignored() {
+    let (mut host, host_rx, address) = host(game(0.2, Some(1), None));
+    let (mut client, _client_rx) = join(&mut host, &host_rx, address);
+    let changed = host.grid.cells().count();
+    let Role::Client { stream, .. } = &mut client.multiplayer.as_mut().unwrap().role else { unreachable!() };
+    for place in [PlaceI32 { x: i32::MAX, y: 0 }, PlaceI32 { x: 0, y: i32::MIN }] {
+        for action in [RemoteAction::Reveal, RemoteAction::Flag, RemoteAction::Chord] {
+            Multiplayer::send(stream, &ClientMessage::Act { action, place }).unwrap();
+            while !next_event(&mut host, &host_rx).starts_with("FromClient(1, Act") {}
+        }
+    }
+    assert_eq!(host.grid.cells().count(), changed);
+}
+
+#[test]
+fn resets_to_worlds_that_cant_be_made_end_the_game() {
+    let (mut host, host_rx, address) = host(game(0.2, Some(1), None));
+    let (mut client, client_rx) = join(&mut host, &host_rx, address);
+    let empty: Layout = serde_json::from_str(r#"{"rows": [""]}"#).unwrap();
+    let full = Layout::parse("*").unwrap();
+    for layout in [empty, full] {
+        let world = HostWorld { generator: GeneratorKind::Layout(layout), ..HostWorld::of(&client) };
+        client.network(NetworkEvent::FromHost(HostMessage::Reset { world }));
+        let event = client_rx.try_iter().find(|event| matches!(event, IoEvent::Panic(_)));
+        assert!(event.is_some());
+    }
+}
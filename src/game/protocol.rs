@@ -0,0 +1,236 @@
+use crate::game::{Game, GameState, WorldConfig};
+use crate::game::auto_play::AutoPlay;
+use crate::game::io::IoEvent;
+use crate::grid::Grid;
+use crate::grid::cell::CellState;
+use crate::grid::topology::Topology;
+use crate::helper::PlaceI32;
+use crate::view::View;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{self, BufRead};
+use std::sync::mpsc::{self, Receiver};
+
+#[cfg(test)]
+mod tests;
+
+/// A command read from one line of input. See PROTOCOL.md.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum Request {
+    Reveal { x: i32, y: i32 },
+    Flag   { x: i32, y: i32 },
+    Chord  { x: i32, y: i32 },
+    View   { x0: i32, y0: i32, w: u32, h: u32 },
+    State,
+}
+
+#[derive(Debug, Serialize)]
+struct ChangedCell {
+    x: i32,
+    y: i32,
+    cell: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct Region {
+    x0: i32,
+    y0: i32,
+    w: u32,
+    h: u32,
+    /// bottom row first
+    rows: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Response {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changed: Option<Vec<ChangedCell>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    view: Option<Region>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mine_concentration: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generator_version: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    topology: Option<Topology>,
+    score: u32,
+    state: GameState,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// Plays a game through JSON lines on stdin and stdout.
+#[derive(Debug)]
+pub struct Protocol {
+    game: Game,
+    /// why the game can't go on, such as a world without a start
+    rx_panic: Receiver<IoEvent>,
+}
+
+impl Protocol {
+    /// Largest area a single `view` command may ask for.
+    pub const MAX_VIEW_CELLS: u64 = 1 << 20;
+
+    pub fn new(config: WorldConfig) -> Protocol {
+        let (tx_panic, rx_panic) = mpsc::channel();
+        let mut game = Game::new(
+            config, Game::HEADLESS_WINDOW_SIZE, false,
+            AutoPlay::new(false, 0, false), Some(tx_panic),
+        );
+        game.record_changes();
+        Protocol { game, rx_panic }
+    }
+
+    /// Answers each line of `input`. If the game can't go on, the last
+    /// line written is an error.
+    pub fn run(&mut self, input: impl BufRead, mut output: impl io::Write) -> io::Result<()> {
+        if let Some(error) = self.failure() {
+            return Self::respond(&mut output, &ErrorResponse { error });
+        }
+        let mut greeting = self.changed(self.opening());
+        greeting.seed = Some(self.game.cell_builder.seed);
+        greeting.mine_concentration = Some(self.game.mine_concentration);
+        greeting.generator_version = Some(self.game.cell_builder.version);
+        greeting.topology = Some(self.game.cell_builder.topology);
+        Self::respond(&mut output, &greeting)?;
+
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() { continue; }
+            let response = serde_json::from_str::<Request>(&line)
+                .map_err(|error| error.to_string())
+                .and_then(|request| self.handle(request));
+            match response {
+                Ok(response) => Self::respond(&mut output, &response)?,
+                Err(error)   => Self::respond(&mut output, &ErrorResponse { error })?,
+            }
+            if let Some(error) = self.failure() {
+                return Self::respond(&mut output, &ErrorResponse { error });
+            }
+        }
+        Ok(())
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.rx_panic.try_iter().find_map(|event| match event {
+            IoEvent::Panic(message) => Some(message.to_string()),
+            _ => None,
+        })
+    }
+
+    fn respond(output: &mut impl io::Write, response: &impl Serialize) -> io::Result<()> {
+        serde_json::to_writer(&mut *output, response)?;
+        writeln!(output)?;
+        output.flush()
+    }
+
+    fn handle(&mut self, request: Request) -> Result<Response, String> {
+        let underway = matches!(self.game.state, GameState::Underway);
+        match request {
+            Request::Reveal { x, y } if underway => self.game.reveal(Self::place(x, y)?),
+            Request::Flag   { x, y } if underway => self.game.toggle_flag(Self::place(x, y)?),
+            Request::Chord  { x, y } if underway => self.game.reveal_adjacent(Self::place(x, y)?),
+            Request::Reveal { .. } | Request::Flag { .. } | Request::Chord { .. } => (),
+            Request::View { x0, y0, w, h } => {
+                if w as u64 * h as u64 > Self::MAX_VIEW_CELLS {
+                    return Err(format!("view is larger than {} cells", Self::MAX_VIEW_CELLS));
+                }
+                let end = |start: i32, length: u32| {
+                    i32::try_from(length).ok().and_then(|length| start.checked_add(length))
+                };
+                let (Some(x1), Some(y1)) = (end(x0, w), end(y0, h)) else {
+                    return Err(Self::out_of_range());
+                };
+                Self::place(x0, y0)?;
+                Self::place(x1, y1)?;
+                let mut response = self.status();
+                response.view = Some(self.region(x0, y0, w, h));
+                return Ok(response);
+            },
+            Request::State => {
+                let mut response = self.status();
+                response.seed = Some(self.game.cell_builder.seed);
+                response.mine_concentration = Some(self.game.mine_concentration);
+                response.generator_version = Some(self.game.cell_builder.version);
+                response.topology = Some(self.game.cell_builder.topology);
+                return Ok(response);
+            },
+        }
+        let changes = self.game.take_changes();
+        Ok(self.changed(changes))
+    }
+
+    fn place(x: i32, y: i32) -> Result<PlaceI32, String> {
+        let place = PlaceI32 { x, y };
+        if !Grid::within_limits(place) {
+            return Err(Self::out_of_range());
+        }
+        Ok(place)
+    }
+
+    fn out_of_range() -> String {
+        format!("coordinates must be within {} of the origin", Grid::MAX_COORDINATE)
+    }
+
+    fn status(&self) -> Response {
+        Response {
+            changed: None,
+            view: None,
+            seed: None,
+            mine_concentration: None,
+            generator_version: None,
+            topology: None,
+            score: self.game.revealed_cell_count,
+            state: self.game.state,
+        }
+    }
+
+    fn changed(&self, changes: Vec<PlaceI32>) -> Response {
+        let show_mines = matches!(self.game.state, GameState::Lost);
+        let changed = changes.into_iter()
+            .map(|place| ChangedCell {
+                x: place.x,
+                y: place.y,
+                cell: View::get_view_cell(&self.game.grid, place, show_mines).char(),
+            })
+            .collect();
+        Response { changed: Some(changed), ..self.status() }
+    }
+
+    /// Cells revealed when the game started, which are connected to the origin.
+    fn opening(&self) -> Vec<PlaceI32> {
+        let mut opening = vec![PlaceI32::ORIGIN];
+        let mut seen = HashSet::from([PlaceI32::ORIGIN]);
+        let mut next = 0;
+        while next < opening.len() {
+            let place = opening[next];
+            next += 1;
+            for neighbour in self.game.grid.neighbours(place) {
+                if let CellState::Revealed = self.game.grid.peek_state(neighbour) &&
+                   seen.insert(neighbour) {
+                    opening.push(neighbour);
+                }
+            }
+        }
+        opening
+    }
+
+    fn region(&self, x0: i32, y0: i32, w: u32, h: u32) -> Region {
+        let show_mines = matches!(self.game.state, GameState::Lost);
+        let rows = (0..h as i32)
+            .map(|y| (0..w as i32)
+                .map(|x| {
+                    let place = PlaceI32 { x: x0 + x, y: y0 + y };
+                    View::get_view_cell(&self.game.grid, place, show_mines).char()
+                })
+                .collect())
+            .collect();
+        Region { x0, y0, w, h, rows }
+    }
+}
@@ -0,0 +1,125 @@
+use super::Protocol;
+use crate::game::WorldConfig;
+use crate::grid::Grid;
+use crate::grid::cell::CellValue;
+use crate::grid::generator::GeneratorKind;
+use crate::grid::topology::Topology;
+use crate::helper::PlaceI32;
+use serde_json::{Value, json};
+use std::io::Cursor;
+
+const SEED: u64 = 1;
+const MINE_CONCENTRATION: f64 = 0.2;
+const VERSION: u8 = 1;
+
+fn config(mine_concentration: f64) -> WorldConfig {
+    WorldConfig {
+        mine_concentration,
+        seed: Some(SEED),
+        generator: GeneratorKind::Uniform,
+        version: VERSION,
+        density_curve: None,
+        topology: Topology::Square,
+    }
+}
+
+fn protocol() -> Protocol {
+    Protocol::new(config(MINE_CONCENTRATION))
+}
+
+/// Runs `commands` and returns the greeting and one response per command.
+fn run(protocol: &mut Protocol, commands: &[Value]) -> Vec<Value> {
+    let input: String = commands.iter().map(|command| format!("{command}\n")).collect();
+    let mut output = Vec::new();
+    protocol.run(Cursor::new(input), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let responses: Vec<Value> = output.lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(responses.len(), commands.len() + 1);
+    responses
+}
+
+#[test]
+fn greeting_describes_the_world_and_opening() {
+    let responses = run(&mut protocol(), &[json!({"command": "state"})]);
+    let greeting = &responses[0];
+    assert_eq!(greeting["seed"], SEED);
+    assert_eq!(greeting["mine_concentration"], MINE_CONCENTRATION);
+    assert_eq!(greeting["generator_version"], VERSION);
+    assert_eq!(greeting["topology"], "square");
+    assert_eq!(greeting["state"], "underway");
+    let changed = greeting["changed"].as_array().unwrap();
+    assert_eq!(changed.len() as u64, greeting["score"].as_u64().unwrap());
+    assert!(changed.contains(&json!({"x": 0, "y": 0, "cell": "0"})));
+
+    let state = &responses[1];
+    assert_eq!(state["seed"], SEED);
+    assert!(state.get("changed").is_none());
+}
+
+#[test]
+fn view_shows_the_opening() {
+    let responses = run(&mut protocol(), &[json!({"command": "view", "x0": -1, "y0": -1, "w": 3, "h": 3})]);
+    let view = &responses[1]["view"];
+    // bottom row first
+    assert_eq!(view["rows"], json!(["110", "000", "000"]));
+}
+
+#[test]
+fn flag_and_reveal_report_changes() {
+    let mut protocol = protocol();
+    let mine = (1..).map(|x| PlaceI32 { x, y: 0 })
+        .find(|&place| matches!(protocol.game.grid.peek(place).value, CellValue::Mine))
+        .unwrap();
+    let responses = run(&mut protocol, &[
+        json!({"command": "flag",   "x": mine.x, "y": mine.y}),
+        json!({"command": "flag",   "x": mine.x, "y": mine.y}),
+        json!({"command": "reveal", "x": mine.x, "y": mine.y}),
+        json!({"command": "reveal", "x": 0, "y": 0}),
+    ]);
+    assert_eq!(responses[1]["changed"], json!([{"x": mine.x, "y": mine.y, "cell": "+"}]));
+    assert_eq!(responses[2]["changed"], json!([{"x": mine.x, "y": mine.y, "cell": " "}]));
+    assert_eq!(responses[3]["changed"], json!([{"x": mine.x, "y": mine.y, "cell": "*"}]));
+    assert_eq!(responses[3]["state"], "lost");
+    // nothing changes once the game is lost
+    assert_eq!(responses[4]["changed"], json!([]));
+}
+
+#[test]
+fn bad_commands_are_answered_with_errors() {
+    let responses = run(&mut protocol(), &[
+        json!({"command": "dig"}),
+        json!({"command": "view", "x0": 0, "y0": 0, "w": 2048, "h": 2048}),
+        json!({"command": "state"}),
+    ]);
+    assert!(responses[1]["error"].is_string());
+    assert_eq!(responses[2]["error"], format!("view is larger than {} cells", Protocol::MAX_VIEW_CELLS));
+    assert_eq!(responses[3]["state"], "underway");
+}
+
+#[test]
+fn coordinates_near_the_limits_are_errors() {
+    let responses = run(&mut protocol(), &[
+        json!({"command": "reveal", "x": i32::MAX, "y": 0}),
+        json!({"command": "chord",  "x": 0, "y": i32::MIN}),
+        json!({"command": "flag",   "x": Grid::MAX_COORDINATE + 1, "y": 0}),
+        json!({"command": "view", "x0": i32::MAX - 1, "y0": 0, "w": 3, "h": 1}),
+        json!({"command": "view", "x0": 0, "y0": Grid::MAX_COORDINATE, "w": 1, "h": 2}),
+        json!({"command": "view", "x0": -Grid::MAX_COORDINATE, "y0": 0, "w": 1, "h": 1}),
+    ]);
+    for response in &responses[1..6] {
+        assert!(response["error"].as_str().unwrap().starts_with("coordinates must be within"));
+    }
+    assert_eq!(responses[6]["view"]["rows"], json!([" "]));
+}
+
+#[test]
+fn worlds_without_a_start_are_an_error() {
+    let mut protocol = Protocol::new(config(1f64));
+    let mut output = Vec::new();
+    protocol.run(Cursor::new("{\"command\": \"state\"}\n"), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(lines, [json!({"error": "cannot find valid start; mine concentration is too high"})]);
+}
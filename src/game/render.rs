@@ -0,0 +1,90 @@
+use crate::game::{Game, WorldConfig};
+use crate::game::auto_play::AutoPlay;
+use crate::game::input::{RenderFormat, RenderInput};
+use crate::grid::Grid;
+use crate::grid::cell_builder::CellBuilder;
+use crate::helper::{PlaceI32, RectI32, SizeI32};
+use crate::view::{View, ViewCell};
+use crossterm::QueueableCommand;
+use crossterm::style::{Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use std::io;
+
+/// Prints part of a world without a terminal user interface.
+#[derive(Debug)]
+pub struct Render {
+    input: RenderInput,
+}
+
+impl Render {
+    /// Area printed when none is given.
+    pub const DEFAULT_AREA: RectI32 = RectI32 {
+        corner: PlaceI32 { x: -20, y: -10 },
+        size: SizeI32 { width: 40, height: 21 },
+    };
+
+    pub fn new(input: RenderInput) -> Render {
+        Render { input }
+    }
+
+    pub fn run(&self, mut buffer: impl io::Write) -> io::Result<()> {
+        self.input.generator.check(self.input.topology)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        let area = self.input.area.unwrap_or(Self::DEFAULT_AREA);
+        let game;
+        let revealed_grid;
+        let grid: &Grid = if self.input.reveal {
+            let cell_builder = CellBuilder::new(
+                self.input.mine_concentration, Some(self.input.seed),
+                &self.input.generator, self.input.generator_version, None, self.input.topology,
+                |message: &'static str| panic!("{}", message),
+            );
+            revealed_grid = Grid::new(cell_builder.revealed());
+            &revealed_grid
+        } else {
+            // the opening a new game starts with
+            let config = WorldConfig {
+                mine_concentration: self.input.mine_concentration,
+                seed: Some(self.input.seed),
+                generator: self.input.generator.clone(),
+                version: self.input.generator_version,
+                density_curve: None,
+                topology: self.input.topology,
+            };
+            game = Game::new(
+                config, Game::HEADLESS_WINDOW_SIZE, self.input.light_mode,
+                AutoPlay::new(false, 0, false), None,
+            );
+            &game.grid
+        };
+
+        for y in (area.corner.y..area.corner.y + area.size.height).rev() {
+            let row: Vec<ViewCell> = (area.corner.x..area.corner.x + area.size.width)
+                .map(|x| View::get_view_cell(grid, PlaceI32 { x, y }, false))
+                .collect();
+            // hex rows are drawn half a cell, which is one character, apart
+            let shift = if grid.topology().tiling().shifted(y) { " " } else { "" };
+            match self.input.format {
+                RenderFormat::Plain => {
+                    let line = row.iter().map(ViewCell::char).collect::<Vec<&str>>().join(" ");
+                    writeln!(buffer, "{}{}", shift, line.trim_end())?;
+                },
+                RenderFormat::Ansi => {
+                    if self.input.light_mode {
+                        buffer.queue(SetBackgroundColor(View::BACKGROUND_COLOR_LIGHT_MODE))?;
+                    }
+                    buffer.queue(Print(shift))?;
+                    for (index, view_cell) in row.iter().enumerate() {
+                        if index != 0 {
+                            buffer.queue(Print(" "))?;
+                        }
+                        buffer.queue(SetForegroundColor(view_cell.color(self.input.light_mode)))?;
+                        buffer.queue(Print(view_cell.char()))?;
+                    }
+                    buffer.queue(ResetColor)?;
+                    buffer.queue(Print("\n"))?;
+                },
+            }
+        }
+        buffer.flush()
+    }
+}
@@ -0,0 +1,71 @@
+use crate::game::GameState;
+use crate::game::multiplayer::CellUpdate;
+use crate::grid::Grid;
+use crate::grid::cell_builder::CellBuilder;
+use crate::grid::density::DensityCurve;
+use crate::grid::generator::GeneratorKind;
+use crate::grid::topology::Topology;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[cfg(test)]
+mod tests;
+
+/// A game written to disk. Only the cells the player changed are kept;
+/// the rest of the world follows from the seed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Save {
+    pub seed: u64,
+    pub mine_concentration: f64,
+    #[serde(default)]
+    pub generator: GeneratorKind,
+    /// saves that don't have a version were generated as version 0
+    #[serde(default)]
+    pub version: u8,
+    #[serde(default)]
+    pub density_curve: Option<DensityCurve>,
+    #[serde(default)]
+    pub topology: Topology,
+    pub state: GameState,
+    pub score: u32,
+    pub hints_used: u32,
+    pub cells: Vec<CellUpdate>,
+}
+
+impl Save {
+    pub fn cells(grid: &Grid) -> Vec<CellUpdate> {
+        grid.cells().map(|(place, cell)| CellUpdate { place, state: cell.state }).collect()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Save> {
+        let save: Save = serde_json::from_str(&fs::read_to_string(path)?)?;
+        GeneratorKind::check_version(save.version)
+            .and_then(|()| save.generator.check(save.topology))
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        Ok(save)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, serde_json::to_string(self)?)
+    }
+
+    /// Rebuilds the grid as it was saved.
+    pub fn grid(&self) -> Grid {
+        let cell_builder = CellBuilder::new(
+            self.mine_concentration, Some(self.seed), &self.generator, self.version, self.density_curve,
+            self.topology,
+            |message: &'static str| panic!("{}", message),
+        );
+        let mut grid = Grid::new(cell_builder);
+        self.apply(&mut grid);
+        grid
+    }
+
+    pub fn apply(&self, grid: &mut Grid) {
+        for &CellUpdate { place, state } in self.cells.iter() {
+            grid.get_mut(place).state = state;
+        }
+    }
+}
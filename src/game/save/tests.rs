@@ -0,0 +1,44 @@
+use super::Save;
+use crate::game::GameState;
+use crate::grid::generator::GeneratorKind;
+use crate::grid::topology::Topology;
+use std::{env, fs, io};
+
+fn save(generator: GeneratorKind, version: u8, topology: Topology) -> Save {
+    Save {
+        seed: 1,
+        mine_concentration: 0.2,
+        generator,
+        version,
+        density_curve: None,
+        topology,
+        state: GameState::Underway,
+        score: 0,
+        hints_used: 0,
+        cells: Vec::new(),
+    }
+}
+
+/// Writes `save` to a file and loads it again.
+fn round_trip(name: &str, save: Save) -> io::Result<Save> {
+    let path = env::temp_dir().join(format!("minesweeper-{}-{name}.json", std::process::id()));
+    save.write(&path)?;
+    let loaded = Save::load(&path);
+    fs::remove_file(&path)?;
+    loaded
+}
+
+#[test]
+fn saves_load() {
+    let loaded = round_trip("valid", save(GeneratorKind::Clustered, GeneratorKind::VERSION, Topology::Hex)).unwrap();
+    assert_eq!(loaded.generator, GeneratorKind::Clustered);
+    assert_eq!(loaded.topology, Topology::Hex);
+}
+
+#[test]
+fn saves_from_newer_versions_are_rejected() {
+    let error = round_trip("newer", save(GeneratorKind::Uniform, GeneratorKind::VERSION + 1, Topology::Square))
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
@@ -0,0 +1,49 @@
+use super::{Action, Game, GameState, WorldConfig};
+use crate::game::auto_play::AutoPlay;
+use crate::grid::generator::GeneratorKind;
+use crate::grid::topology::Topology;
+
+const SEED: u64 = 1;
+const MINE_CONCENTRATION: f64 = 0.2;
+const VERSION: u8 = 1;
+
+/// A game that goes on the leaderboard, as one started from the command line would.
+fn game() -> Game {
+    let config = WorldConfig {
+        mine_concentration: MINE_CONCENTRATION,
+        seed: Some(SEED),
+        generator: GeneratorKind::Uniform,
+        version: VERSION,
+        density_curve: None,
+        topology: Topology::Square,
+    };
+    let mut game = Game::new(
+        config, Game::HEADLESS_WINDOW_SIZE, false,
+        AutoPlay::new(false, 0, false), None,
+    );
+    game.record_scores = true;
+    game
+}
+
+#[test]
+fn quitting_keeps_games_played_without_help() {
+    let game = game();
+    let entry = game.quit_entry().unwrap();
+    assert!(entry.score > 0);
+    assert_eq!(entry.score, game.revealed_cell_count);
+    assert_eq!((entry.seed, entry.hints_used), (SEED, 0));
+
+    let mut hinted = self::game();
+    hinted.action(Action::Hint);
+    assert_eq!(hinted.hints_used, 1);
+    assert!(hinted.quit_entry().is_none());
+
+    let mut auto_played = self::game();
+    auto_played.action(Action::ToggleAutoPlay);
+    assert!(auto_played.quit_entry().is_none());
+
+    // lost games were recorded when they ended
+    let mut lost = self::game();
+    lost.state = GameState::Lost;
+    assert!(lost.quit_entry().is_none());
+}
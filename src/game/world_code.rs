@@ -0,0 +1,166 @@
+use crate::grid::generator::GeneratorKind;
+use crate::grid::topology::Topology;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests;
+
+/// Everything needed to recreate a world, as a short code that can be
+/// typed in by hand, like `0G5M-6Q9V-...`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorldCode {
+    pub seed: u64,
+    pub mine_concentration: f64,
+    /// rule variant, which is the topology, see `Topology::code`
+    pub rules: u8,
+    /// which generator places the mines, see `GeneratorKind::code`
+    pub generator: u8,
+    /// version of world generation, sharing a byte with the generator
+    pub version: u8,
+}
+
+impl WorldCode {
+    /// Mine concentrations are stored in steps of this size.
+    const MINE_CONCENTRATION_STEPS: f64 = 10000f64;
+    const LENGTH: usize = 14;
+    const GROUP: usize = 4;
+    // crockford base32, which avoids letters that are easily confused
+    const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+    /// Returns `None` if the mine concentration isn't between 0 and 1 in
+    /// steps that can be stored exactly, or the generator is a layout,
+    /// which doesn't fit in a code.
+    pub fn new(
+        seed: u64,
+        mine_concentration: f64,
+        generator: &GeneratorKind,
+        version: u8,
+        topology: Topology,
+    ) -> Option<WorldCode> {
+        let steps = (mine_concentration * Self::MINE_CONCENTRATION_STEPS).round();
+        if !(0f64..=Self::MINE_CONCENTRATION_STEPS).contains(&steps) ||
+           steps / Self::MINE_CONCENTRATION_STEPS != mine_concentration {
+            return None;
+        }
+        Some(WorldCode {
+            seed,
+            mine_concentration,
+            rules: topology.code(),
+            generator: generator.code()?,
+            version,
+        })
+    }
+
+    pub fn generator_kind(&self) -> GeneratorKind {
+        GeneratorKind::from_code(self.generator).expect("parsed world codes have a known generator")
+    }
+
+    pub fn topology(&self) -> Topology {
+        Topology::from_code(self.rules).expect("parsed world codes have known rules")
+    }
+
+    fn bytes(&self) -> [u8; Self::LENGTH] {
+        let steps = (self.mine_concentration * Self::MINE_CONCENTRATION_STEPS).round() as u16;
+        let mut bytes = [0; Self::LENGTH];
+        bytes[ 0..8 ].copy_from_slice(&self.seed.to_be_bytes());
+        bytes[ 8..10].copy_from_slice(&steps.to_be_bytes());
+        bytes[10] = self.rules;
+        // codes from before versions had only the generator, so version 0
+        bytes[11] = self.version << 4 | self.generator;
+        let checksum = Self::checksum(&bytes[0..12]);
+        bytes[12..14].copy_from_slice(&checksum.to_be_bytes());
+        bytes
+    }
+
+    fn checksum(bytes: &[u8]) -> u16 {
+        let hash = hash_text(bytes);
+        (hash ^ (hash >> 16) ^ (hash >> 32) ^ (hash >> 48)) as u16
+    }
+}
+
+impl fmt::Display for WorldCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut characters = Vec::new();
+        let (mut buffer, mut bits) = (0u32, 0);
+        for byte in self.bytes() {
+            buffer = buffer << 8 | byte as u32;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                characters.push(Self::ALPHABET[(buffer >> bits) as usize & 0x1f]);
+            }
+        }
+        if bits > 0 {
+            characters.push(Self::ALPHABET[(buffer << (5 - bits)) as usize & 0x1f]);
+        }
+        let groups: Vec<&str> = characters.chunks(Self::GROUP)
+            .map(|group| std::str::from_utf8(group).expect("alphabet is ascii"))
+            .collect();
+        write!(f, "{}", groups.join("-"))
+    }
+}
+
+impl FromStr for WorldCode {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<WorldCode, String> {
+        let mut bytes = Vec::new();
+        let (mut buffer, mut bits) = (0u32, 0);
+        for character in text.chars() {
+            let character = match character.to_ascii_uppercase() {
+                '-' | ' ' => continue,
+                'O' => '0',
+                'I' | 'L' => '1',
+                character => character,
+            };
+            let Some(value) = Self::ALPHABET.iter().position(|&letter| letter as char == character) else {
+                return Err(format!("invalid character `{character}` in world code"));
+            };
+            buffer = buffer << 5 | value as u32;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                bytes.push((buffer >> bits) as u8);
+            }
+        }
+        let Ok(bytes) = <[u8; Self::LENGTH]>::try_from(bytes) else {
+            return Err("world code has the wrong length".to_string());
+        };
+        if Self::checksum(&bytes[0..12]).to_be_bytes() != bytes[12..14] {
+            return Err("world code is mistyped; the checksum doesn't match".to_string());
+        }
+        let code = WorldCode {
+            seed: u64::from_be_bytes(bytes[0..8].try_into().expect("slice has 8 bytes")),
+            mine_concentration: u16::from_be_bytes([bytes[8], bytes[9]]) as f64
+                / Self::MINE_CONCENTRATION_STEPS,
+            rules: bytes[10],
+            generator: bytes[11] & 0x0f,
+            version: bytes[11] >> 4,
+        };
+        if code.mine_concentration > 1f64 {
+            return Err(format!("world code has mine concentration {}, more than 1", code.mine_concentration));
+        }
+        if Topology::from_code(code.rules).is_none() {
+            return Err(format!("world code uses rules {} that this version doesn't know", code.rules));
+        }
+        if GeneratorKind::from_code(code.generator).is_none() {
+            return Err(format!("world code uses generator {} that this version doesn't know", code.generator));
+        }
+        GeneratorKind::check_version(code.version)?;
+        Ok(code)
+    }
+}
+
+/// Parses a seed given on the command line. Numbers are used as they
+/// are; any other text is hashed, so worlds can have names.
+pub fn parse_seed(text: &str) -> Result<u64, String> {
+    Ok(text.parse().unwrap_or_else(|_| hash_text(text.as_bytes())))
+}
+
+/// 64 bit FNV-1a, which unlike `std`'s hasher is fixed forever.
+fn hash_text(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
@@ -0,0 +1,73 @@
+use super::WorldCode;
+use crate::grid::generator::{GeneratorKind, Layout};
+use crate::grid::topology::Topology;
+
+const VERSION: u8 = 1;
+
+fn code(seed: u64, mine_concentration: f64, generator: GeneratorKind, topology: Topology) -> WorldCode {
+    WorldCode::new(seed, mine_concentration, &generator, VERSION, topology).unwrap()
+}
+
+#[test]
+fn codes_round_trip() {
+    for (seed, mine_concentration, generator, topology) in [
+        (0, 0f64, GeneratorKind::Uniform, Topology::Square),
+        (1, 0.2, GeneratorKind::Clustered, Topology::Hex),
+        (u64::MAX, 1f64, GeneratorKind::Stratified, Topology::Orthogonal),
+        (0x0123_4567_89ab_cdef, 0.1234, GeneratorKind::Noise, Topology::Radius2),
+    ] {
+        let code = code(seed, mine_concentration, generator.clone(), topology);
+        let text = code.to_string();
+        assert_eq!(text.len(), 23 + 5, "{text}");
+        let parsed: WorldCode = text.parse().unwrap();
+        assert_eq!(parsed, code);
+        assert_eq!(parsed.generator_kind(), generator);
+        assert_eq!(parsed.topology(), topology);
+        assert_eq!(parsed.version, VERSION);
+    }
+}
+
+#[test]
+fn concentrations_that_cant_be_stored_have_no_code() {
+    for mine_concentration in [-0.1, 0.12345, 1.0001, 6f64] {
+        let code = WorldCode::new(1, mine_concentration, &GeneratorKind::Uniform, VERSION, Topology::Square);
+        assert_eq!(code, None, "{mine_concentration}");
+    }
+    let layout = GeneratorKind::Layout(Layout::parse("*....").unwrap());
+    assert_eq!(WorldCode::new(1, 0.2, &layout, VERSION, Topology::Square), None);
+}
+
+#[test]
+fn mistyped_codes_fail_the_checksum() {
+    let text = code(42, 0.2, GeneratorKind::Uniform, Topology::Square).to_string();
+    for index in (0..text.len()).filter(|&index| &text[index..=index] != "-") {
+        let replacement = if &text[index..=index] == "7" { "8" } else { "7" };
+        let mistyped = format!("{}{replacement}{}", &text[..index], &text[index + 1..]);
+        assert!(mistyped.parse::<WorldCode>().is_err(), "{mistyped}");
+    }
+}
+
+#[test]
+fn confusable_letters_are_read_as_digits() {
+    // seed 0 and concentration 1 give codes with both 0 and 1 in them
+    let code = code(0x0101_0101_0101_0101, 1f64, GeneratorKind::Uniform, Topology::Square);
+    let text = code.to_string();
+    assert!(text.contains('0') && text.contains('1'), "{text}");
+    let typed = text.replace('0', "o").replace('1', "I").to_lowercase().replace('-', " ");
+    assert_eq!(typed.parse::<WorldCode>(), Ok(code));
+    let typed = text.replace('1', "L").replace('-', "");
+    assert_eq!(typed.parse::<WorldCode>(), Ok(code));
+}
+
+#[test]
+fn concentrations_above_1_are_rejected() {
+    let code = WorldCode { seed: 1, mine_concentration: 2f64, rules: 0, generator: 0, version: VERSION };
+    assert!(code.to_string().parse::<WorldCode>().is_err());
+}
+
+#[test]
+fn codes_from_newer_versions_are_rejected() {
+    let code = code(1, 0.2, GeneratorKind::Uniform, Topology::Square);
+    let code = WorldCode { version: GeneratorKind::VERSION + 1, ..code };
+    assert!(code.to_string().parse::<WorldCode>().is_err());
+}
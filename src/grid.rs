@@ -1,43 +1,265 @@
-use self::cell::{Cell, CellValue};
-use crate::helper::{Immut, PlaceI32};
+use self::cell::{Cell, CellState, CellValue};
+use crate::helper::{PlaceI32, RectI32, SizeI32};
 use self::cell_builder::CellBuilder;
-use self::tile::{Tile, Subtiles};
-use std::cell::{RefCell, RefMut};
+use self::chunk::{Chunk, Chunks};
+use self::topology::Topology;
+use std::ops::{Deref, DerefMut};
 
 pub mod cell;
 pub mod cell_builder;
-mod tile;
+pub mod density;
+pub mod generator;
+pub mod topology;
+mod chunk;
 
+#[cfg(test)]
+mod tests;
+
+/// The infinite grid of cells. Only cells the player changed are stored,
+/// in chunks kept in a hash map; every other cell is generated when it
+/// is looked at. Looking at the grid never changes it, so memory grows
+/// with how much is played rather than how far the camera goes.
 #[derive(Debug)]
 pub struct Grid {
-    tile: RefCell<Subtiles>,
+    chunks: Chunks,
+    builder: CellBuilder,
+    revealed_count: usize,
+    flag_count: usize,
 }
 
 impl Grid {
+    /// Largest coordinate a place may have, leaving room for neighbours
+    /// and for where the world's start was found.
+    pub const MAX_COORDINATE: i32 = i32::MAX - 1024;
+
     pub fn new(cell_builder: CellBuilder) -> Grid {
         Self {
-            tile: RefCell::new(
-                Tile::new(1, cell_builder),
-            ),
+            chunks: Chunks::new(),
+            builder: cell_builder,
+            revealed_count: 0,
+            flag_count: 0,
+        }
+    }
+
+    // queries
+
+    /// Whether `place` is within `MAX_COORDINATE` of the origin, so that
+    /// it and its neighbours can be generated.
+    pub fn within_limits(place: PlaceI32) -> bool {
+        let within = |coordinate: i32| {
+            coordinate.checked_abs().is_some_and(|distance| distance <= Self::MAX_COORDINATE)
+        };
+        within(place.x) && within(place.y)
+    }
+
+    /// The cell at `place`, as stored or as generated.
+    pub fn peek(&self, place: PlaceI32) -> Cell {
+        match self.peek_stored(place) {
+            Some((cell, _)) => cell,
+            None => self.builder.cell(place),
         }
     }
 
-    pub fn get(&self, place: PlaceI32) -> Immut<Cell> {
-        if place.radius() > self.tile.borrow().radius {
-            self.tile.borrow_mut().expand();
-            self.get(place)
-        } else {
-            Immut::new(self.tile.borrow_mut().get(place).clone())
+    /// The state of the cell at `place`, without generating it.
+    pub fn peek_state(&self, place: PlaceI32) -> CellState {
+        match self.peek_stored(place) {
+            Some((cell, _)) => cell.state,
+            None => self.builder.state(),
         }
     }
 
-    pub fn get_mut(&mut self, place: PlaceI32) -> RefMut<'_, Cell> {
-        if place.radius() > self.tile.borrow().radius {
-            self.tile.borrow_mut().expand();
-            self.get_mut(place)
-        } else {
-            let tile = self.tile.borrow_mut();
-            RefMut::map(tile, |tile| tile.get(place))
+    /// The cell and its number of adjacent mines, if the cell is stored
+    /// because the player changed it. The number is stored for revealed
+    /// cells.
+    pub fn peek_stored(&self, place: PlaceI32) -> Option<(Cell, Option<u8>)> {
+        let (chunk, index) = Chunk::locate(place);
+        let chunk = self.chunks.get(chunk)?;
+        Some((chunk.get(index)?, chunk.mine_count(index)))
+    }
+
+    /// Number of mines adjacent to `place`. It is stored with cells when
+    /// they are revealed, as cells never change whether they are mines.
+    pub fn mine_count(&self, place: PlaceI32) -> u8 {
+        match self.peek_stored(place) {
+            Some((_, Some(count))) => count,
+            _ => self.count_mines(place),
+        }
+    }
+
+    /// Chance of a mine at `place`, which only varies with a density
+    /// curve.
+    pub fn mine_concentration(&self, place: PlaceI32) -> f64 {
+        self.builder.mine_concentration_at(place)
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.builder.topology
+    }
+
+    /// The cells whose mines the number at `place` counts.
+    pub fn neighbours(&self, place: PlaceI32) -> impl Iterator<Item = PlaceI32> {
+        self.builder.topology.neighbours(place)
+    }
+
+    /// Every cell whose state differs from the state cells start in, in
+    /// no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = (PlaceI32, Cell)> + '_ {
+        let start_state = self.builder.state();
+        self.chunks.iter()
+            .flat_map(|(chunk_place, chunk)| chunk.cells().map(move |(index, cell)| {
+                (Chunk::place(chunk_place, index), cell)
+            }))
+            .filter(move |(_, cell)| cell.state != start_state)
+    }
+
+    /// The cells of `cells` within `area`, looking only at the chunks
+    /// that overlap it.
+    pub fn cells_in(&self, area: RectI32) -> impl Iterator<Item = (PlaceI32, Cell)> + '_ {
+        let start_state = self.builder.state();
+        // areas running past the largest coordinate stop at it
+        let far = |start: i32, length: i32| start.saturating_add(length.max(1) - 1);
+        let (low, _) = Chunk::locate(area.corner);
+        let (high, _) = Chunk::locate(PlaceI32 {
+            x: far(area.corner.x, area.size.width),
+            y: far(area.corner.y, area.size.height),
+        });
+        let chunk_area = RectI32 {
+            corner: low,
+            size: SizeI32 { width: high.x - low.x + 1, height: high.y - low.y + 1 },
+        };
+        // for large areas, going through the stored chunks is quicker
+        let chunk_count = chunk_area.size.width as i64 * chunk_area.size.height as i64;
+        let chunks: Box<dyn Iterator<Item = (PlaceI32, &Chunk)>> =
+            if area.size.width <= 0 || area.size.height <= 0 {
+                Box::new(std::iter::empty())
+            } else if chunk_count > self.chunks.len() as i64 {
+                Box::new(self.chunks.iter().filter(move |&(place, _)| chunk_area.contains(place)))
+            } else {
+                Box::new(chunk_area.places().filter_map(|place| Some((place, self.chunks.get(place)?))))
+            };
+        chunks
+            .flat_map(|(chunk_place, chunk)| chunk.cells().map(move |(index, cell)| {
+                (Chunk::place(chunk_place, index), cell)
+            }))
+            .filter(move |&(place, cell)| cell.state != start_state && area.contains(place))
+    }
+
+    /// Number of cells the player revealed, counted as they change.
+    pub fn revealed_count(&self) -> usize {
+        self.revealed_count
+    }
+
+    /// Number of flagged cells.
+    pub fn flag_count(&self) -> usize {
+        self.flag_count
+    }
+
+    /// Smallest rectangle around every cell of `cells`.
+    pub fn bounding_box(&self) -> Option<RectI32> {
+        let mut bounds: Option<(PlaceI32, PlaceI32)> = None;
+        for (place, _) in self.cells() {
+            let (low, high) = bounds.get_or_insert((place, place));
+            low.x  = low.x .min(place.x);
+            low.y  = low.y .min(place.y);
+            high.x = high.x.max(place.x);
+            high.y = high.y.max(place.y);
         }
+        let (low, high) = bounds?;
+        Some(RectI32 {
+            corner: low,
+            size: SizeI32 { width: high.x - low.x + 1, height: high.y - low.y + 1 },
+        })
     }
-}
\ No newline at end of file
+
+    /// Approximate heap memory held by the grid, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.chunks.memory_usage()
+    }
+
+    fn count_mines(&self, place: PlaceI32) -> u8 {
+        let mut count = 0;
+        for place in self.neighbours(place) {
+            if let Cell { value: CellValue::Mine, .. } = self.peek(place) {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    // mutations
+
+    /// The cell at `place` for changing. It is stored when the change is
+    /// dropped, if its state changed.
+    pub fn get_mut(&mut self, place: PlaceI32) -> CellMut<'_> {
+        let cell = self.peek(place);
+        CellMut { cell, state_before: cell.state, place, grid: self }
+    }
+
+    fn state_count(&mut self, state: CellState) -> Option<&mut usize> {
+        match state {
+            CellState::Revealed => Some(&mut self.revealed_count),
+            CellState::Flagged  => Some(&mut self.flag_count),
+            CellState::Hidden   => None,
+        }
+    }
+
+    fn store(&mut self, place: PlaceI32, cell: Cell, state_before: CellState) {
+        // the counts only include cells changed from how they start
+        let start_state = self.builder.state();
+        if state_before != start_state && let Some(count) = self.state_count(state_before) {
+            *count -= 1;
+        }
+        if cell.state != start_state && let Some(count) = self.state_count(cell.state) {
+            *count += 1;
+        }
+        let stored = self.peek_stored(place);
+        let (chunk, index) = Chunk::locate(place);
+        if cell.state == start_state && !matches!(stored, Some((_, Some(_)))) {
+            // back to how it started, with nothing worth keeping
+            self.chunks.remove(chunk, index);
+            return;
+        }
+        let mine_count = match (cell.state, stored) {
+            (CellState::Revealed, Some((_, Some(_)))) => None,
+            (CellState::Revealed, _) => Some(self.count_mines(place)),
+            _ => None,
+        };
+        let chunk = self.chunks.get_or_insert(chunk);
+        chunk.set(index, cell);
+        if let Some(count) = mine_count {
+            chunk.set_mine_count(index, count);
+        }
+    }
+}
+
+/// A cell borrowed from the grid for changing. Changes are packed into
+/// the grid when it is dropped.
+#[derive(Debug)]
+pub struct CellMut<'a> {
+    cell: Cell,
+    state_before: CellState,
+    place: PlaceI32,
+    grid: &'a mut Grid,
+}
+
+impl Deref for CellMut<'_> {
+    type Target = Cell;
+
+    fn deref(&self) -> &Cell {
+        &self.cell
+    }
+}
+
+impl DerefMut for CellMut<'_> {
+    fn deref_mut(&mut self) -> &mut Cell {
+        &mut self.cell
+    }
+}
+
+impl Drop for CellMut<'_> {
+    fn drop(&mut self) {
+        if self.cell.state != self.state_before {
+            self.grid.store(self.place, self.cell, self.state_before);
+        }
+    }
+}
@@ -1,82 +1,88 @@
-use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use std::sync::Arc;
 
-use super::{PlaceI32, Cell, CellValue};
+use super::{PlaceI32, Cell, CellState, CellValue};
+use super::density::DensityCurve;
+use super::generator::{Generator, GeneratorKind};
+use super::topology::Topology;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct CellBuilder {
+    /// mine concentration at the start
     pub mine_concentration: f64,
     pub seed: u64,
+    generator: Arc<dyn Generator>,
+    /// version of world generation, see `GeneratorKind::VERSION`
+    pub version: u8,
+    pub density_curve: Option<DensityCurve>,
+    pub topology: Topology,
     origin: PlaceI32,
+    /// build cells already revealed, to look at the whole world
+    reveal: bool,
 }
 
 impl CellBuilder {
-    pub const DUMMY: CellBuilder = CellBuilder {
-        mine_concentration: 0f64,
-        seed: 0,
-        origin: PlaceI32 {
-            x: 0,
-            y: 0,
-        },
-    };
-
     pub fn new(
         mine_concentration: f64,
         seed: Option<u64>,
+        generator: &GeneratorKind,
+        version: u8,
+        density_curve: Option<DensityCurve>,
+        topology: Topology,
         send_panic: impl FnOnce(&'static str),
     ) -> CellBuilder {
-        let seed = seed.unwrap_or_else(|| Self::get_random_seed());
-        let origin =
-            Self::first_valid_start(seed, mine_concentration, send_panic);
-        CellBuilder {
+        let mut cell_builder = CellBuilder {
             mine_concentration,
-            seed,
-            origin,
-        }
+            seed: seed.unwrap_or_else(Self::get_random_seed),
+            generator: generator.generator(version),
+            version,
+            density_curve,
+            topology,
+            origin: PlaceI32::ORIGIN,
+            reveal: false,
+        };
+        cell_builder.origin = cell_builder.first_valid_start(send_panic);
+        cell_builder
+    }
+
+    pub fn revealed(self) -> CellBuilder {
+        CellBuilder { reveal: true, ..self }
     }
 
     pub fn cell(&self, place: PlaceI32) -> Cell {
-        let value =
-        Self::cell_value_before_origin(
-            self.seed,
-            self.mine_concentration,
-            PlaceI32 {
-                x: place.x + self.origin.x,
-                y: place.y + self.origin.y
-            },
-        );
-        let cell = Cell::new(value);
-        // let mut cell = Cell::new(value); cell.reveal();
-        cell
+        Cell { value: self.value(place, self.origin), state: self.state() }
     }
 
-    fn cell_value_before_origin(
-        seed_u64: u64,
-        mine_concentration: f64,
-        place: PlaceI32,
-    ) -> CellValue {
-        let mut seed = [42; 32];
-        seed[ 0..8 ].copy_from_slice(&seed_u64.to_be_bytes());
-        seed[ 8..12].copy_from_slice(&place.x .to_be_bytes());
-        seed[12..16].copy_from_slice(&place.y .to_be_bytes());
+    /// Chance of a mine at `place`, measured from the start.
+    pub fn mine_concentration_at(&self, place: PlaceI32) -> f64 {
+        match self.density_curve {
+            Some(curve) => curve.mine_concentration(self.mine_concentration, place),
+            None => self.mine_concentration,
+        }
+    }
 
-        let mut rng = StdRng::from_seed(seed);
+    /// State cells start in.
+    pub fn state(&self) -> CellState {
+        if self.reveal { CellState::Revealed } else { CellState::Hidden }
+    }
 
-        if mine_concentration > rng.random() {
-            CellValue::Mine
-        } else {
-            CellValue::Empty
-        }
+    /// The value of the cell at `place` if the start were at `origin`.
+    fn value(&self, place: PlaceI32, origin: PlaceI32) -> CellValue {
+        self.generator.value(
+            self.seed,
+            PlaceI32 {
+                x: place.x.wrapping_add(origin.x),
+                y: place.y.wrapping_add(origin.y),
+            },
+            self.mine_concentration_at(place),
+        )
     }
 
-    fn first_valid_start(
-        seed: u64,
-        mine_concentration: f64,
-        send_panic: impl FnOnce(&'static str),
-    ) -> PlaceI32 {
+    fn first_valid_start(&self, send_panic: impl FnOnce(&'static str)) -> PlaceI32 {
         for x in -500..500 {
             for y in -500..500 {
                 let place = PlaceI32 { x, y };
-                if Self::is_clear(seed, mine_concentration, place) {
+                if self.is_clear(place) {
                     return place;
                 }
             }
@@ -85,17 +91,14 @@ impl CellBuilder {
         PlaceI32 { x: 0, y: 0 } // return dummy value
     }
 
-    fn is_clear(seed: u64, mine_concentration: f64, place: PlaceI32) -> bool {
-        for x in -1..=1 {
-            for y in -1..=1 {
-                let place = PlaceI32 {
-                    x: place.x + x,
-                    y: place.y + y,
-                };
-                let cell_value = Self::cell_value_before_origin(seed, mine_concentration, place);
-                if let CellValue::Mine = cell_value {
-                    return false;
-                }
+    /// Whether the start and its neighbours have no mines, if the start
+    /// were at `origin`.
+    fn is_clear(&self, origin: PlaceI32) -> bool {
+        let start = PlaceI32::ORIGIN;
+        for place in std::iter::once(start).chain(self.topology.neighbours(start)) {
+            let cell_value = self.value(place, origin);
+            if let CellValue::Mine = cell_value {
+                return false;
             }
         }
         true
@@ -106,4 +109,4 @@ impl CellBuilder {
         rng.next_u64()
         // ;0xDEADBEEF
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,174 @@
+use super::{Cell, CellValue, PlaceI32};
+use super::cell::CellState;
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// Chunks by position.
+#[derive(Debug)]
+pub struct Chunks {
+    map: HashMap<PlaceI32, Box<Chunk>, BuildHasherDefault<ChunkHasher>>,
+}
+
+impl Chunks {
+    pub fn new() -> Chunks {
+        Chunks { map: HashMap::default() }
+    }
+
+    pub fn get(&self, place: PlaceI32) -> Option<&Chunk> {
+        self.map.get(&place).map(|chunk| &**chunk)
+    }
+
+    /// The chunk at `place`, which is created if missing.
+    pub fn get_or_insert(&mut self, place: PlaceI32) -> &mut Chunk {
+        self.map.entry(place).or_insert_with(|| Box::new(Chunk::new()))
+    }
+
+    /// Forgets the cell at `index` in the chunk at `place`, dropping the
+    /// chunk once nothing is left in it.
+    pub fn remove(&mut self, place: PlaceI32, index: usize) {
+        if let Some(chunk) = self.map.get_mut(&place) {
+            chunk.cells[index] = 0;
+            if chunk.cells.iter().all(|&packed| packed == 0) {
+                self.map.remove(&place);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (PlaceI32, &Chunk)> {
+        self.map.iter().map(|(&place, chunk)| (place, &**chunk))
+    }
+
+    /// Approximate heap memory held by the chunks, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        // each bucket of the table also has a control byte
+        let table = self.map.capacity() * (std::mem::size_of::<(PlaceI32, Box<Chunk>)>() + 1);
+        table + self.map.len() * std::mem::size_of::<Chunk>()
+    }
+}
+
+/// Square of cells stored together, each packed into a byte along with
+/// its number of adjacent mines once that is known. Places without a
+/// stored cell are zero.
+#[derive(Debug)]
+pub struct Chunk {
+    cells: [u8; Chunk::AREA],
+}
+
+impl Chunk {
+    pub const SIZE: i32 = 32;
+    const AREA: usize = (Self::SIZE * Self::SIZE) as usize;
+    const SHIFT: u32 = Self::SIZE.trailing_zeros();
+
+    const STORED: u8 = 0b1000_0000;
+    const MINE:      u8 = 0b0000_0001;
+    const FLAGGED:   u8 = 0b0000_0010;
+    const REVEALED:  u8 = 0b0000_0100;
+    /// adjacent mines plus one, or zero if not counted yet
+    const MINE_COUNT: u8 = 0b0111_1000;
+    const MINE_COUNT_SHIFT: u32 = Self::MINE_COUNT.trailing_zeros();
+    /// Largest count that fits; larger ones are counted again when needed.
+    const MAX_MINE_COUNT: u8 = (Self::MINE_COUNT >> Self::MINE_COUNT_SHIFT) - 1;
+
+    pub fn new() -> Chunk {
+        Chunk { cells: [0; Self::AREA] }
+    }
+
+    /// The chunk containing `place`, and the index of `place` within it.
+    pub fn locate(place: PlaceI32) -> (PlaceI32, usize) {
+        let chunk = PlaceI32 { x: place.x >> Self::SHIFT, y: place.y >> Self::SHIFT };
+        let x = (place.x & (Self::SIZE - 1)) as usize;
+        let y = (place.y & (Self::SIZE - 1)) as usize;
+        (chunk, y * Self::SIZE as usize + x)
+    }
+
+    /// Inverse of `locate`.
+    pub fn place(chunk: PlaceI32, index: usize) -> PlaceI32 {
+        PlaceI32 {
+            x: (chunk.x << Self::SHIFT) + (index % Self::SIZE as usize) as i32,
+            y: (chunk.y << Self::SHIFT) + (index / Self::SIZE as usize) as i32,
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<Cell> {
+        Self::unpack(self.cells[index])
+    }
+
+    /// Stores `cell`, keeping its mine count.
+    pub fn set(&mut self, index: usize, cell: Cell) {
+        self.cells[index] = Self::pack(cell) | self.cells[index] & Self::MINE_COUNT;
+    }
+
+    pub fn mine_count(&self, index: usize) -> Option<u8> {
+        match (self.cells[index] & Self::MINE_COUNT) >> Self::MINE_COUNT_SHIFT {
+            0 => None,
+            count => Some(count - 1),
+        }
+    }
+
+    pub fn set_mine_count(&mut self, index: usize, count: u8) {
+        if count > Self::MAX_MINE_COUNT { return; }
+        self.cells[index] = self.cells[index] & !Self::MINE_COUNT | (count + 1) << Self::MINE_COUNT_SHIFT;
+    }
+
+    /// Stored cells with their indices.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, Cell)> + '_ {
+        self.cells.iter().enumerate()
+            .filter_map(|(index, &packed)| Some((index, Self::unpack(packed)?)))
+    }
+
+    fn pack(cell: Cell) -> u8 {
+        let value = match cell.value {
+            CellValue::Mine  => Self::MINE,
+            CellValue::Empty => 0,
+        };
+        let state = match cell.state {
+            CellState::Hidden   => 0,
+            CellState::Flagged  => Self::FLAGGED,
+            CellState::Revealed => Self::REVEALED,
+        };
+        Self::STORED | value | state
+    }
+
+    fn unpack(packed: u8) -> Option<Cell> {
+        if packed & Self::STORED == 0 { return None; }
+        let value = if packed & Self::MINE != 0 { CellValue::Mine } else { CellValue::Empty };
+        let state = if packed & Self::REVEALED != 0 {
+            CellState::Revealed
+        } else if packed & Self::FLAGGED != 0 {
+            CellState::Flagged
+        } else {
+            CellState::Hidden
+        };
+        Some(Cell { value, state })
+    }
+}
+
+/// Hashes chunk positions with a multiply and rotate, which is much
+/// faster than the default hasher and good enough for small keys.
+#[derive(Debug, Default)]
+pub struct ChunkHasher(u64);
+
+impl Hasher for ChunkHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u32(byte as u32);
+        }
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.write_u32(value as u32);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.0 = (self.0.rotate_left(5) ^ value as u64).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+
+    fn finish(&self) -> u64 {
+        // the table picks buckets with the low bits, which a multiply mixes the least
+        self.0.rotate_left(26)
+    }
+}
@@ -0,0 +1,19 @@
+use super::PlaceI32;
+use serde::{Deserialize, Serialize};
+
+/// Mine concentration that rises, or falls, with distance from the
+/// start, from the concentration of the game at the start to `far` at
+/// `radius` cells and beyond.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DensityCurve {
+    pub far: f64,
+    pub radius: f64,
+}
+
+impl DensityCurve {
+    pub fn mine_concentration(&self, near: f64, place: PlaceI32) -> f64 {
+        let distance = (place.x as f64).hypot(place.y as f64);
+        let progress = (distance / self.radius).min(1f64);
+        near + (self.far - near) * progress
+    }
+}
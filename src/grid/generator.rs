@@ -0,0 +1,348 @@
+use super::{CellValue, PlaceI32};
+use super::topology::Topology;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::sync::Arc;
+use std::{fmt, fs};
+
+#[cfg(test)]
+mod tests;
+
+/// Decides which cells are mines. The same seed, place and mine
+/// concentration must always give the same value, as cells are
+/// generated again whenever they are looked at. The concentration is
+/// that around `place`, which changes across the world with a density
+/// curve.
+pub trait Generator: fmt::Debug + Send + Sync {
+    fn value(&self, seed: u64, place: PlaceI32, mine_concentration: f64) -> CellValue;
+}
+
+/// The generators that can be picked with `--generator`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneratorKind {
+    /// every cell is a mine with the same probability
+    #[default]
+    Uniform,
+    /// mines gather in clumps with sparse ground between them
+    Clustered,
+    /// the density of mines drifts smoothly across the world
+    Noise,
+    /// every 8x8 block has the same number of mines
+    Stratified,
+    /// a fixed layout read from a file, repeated in every direction
+    Layout(Layout),
+}
+
+impl GeneratorKind {
+    /// Version of world generation that new worlds use. A seed only gives
+    /// the same world with the same version, so old versions are kept:
+    ///
+    /// 0. uniform mines come from a ChaCha12 generator seeded for every cell
+    /// 1. uniform mines come from a hash of the seed and place
+    pub const VERSION: u8 = 1;
+
+    /// Checks that `version` is one this build knows how to generate.
+    pub fn check_version(version: u8) -> Result<(), String> {
+        if version > Self::VERSION {
+            return Err(format!(
+                "world is from generator version {version}, newer than this one ({})", Self::VERSION,
+            ));
+        }
+        Ok(())
+    }
+
+    /// The generator for `version`, which must have been checked with
+    /// `check_version`.
+    pub fn generator(&self, version: u8) -> Arc<dyn Generator> {
+        assert!(version <= Self::VERSION, "unknown generator version {version}");
+        match self {
+            GeneratorKind::Uniform if version == 0 => Arc::new(SeededUniform),
+            GeneratorKind::Uniform    => Arc::new(Uniform),
+            GeneratorKind::Clustered  => Arc::new(Clustered),
+            GeneratorKind::Noise      => Arc::new(Noise),
+            GeneratorKind::Stratified => Arc::new(Stratified),
+            GeneratorKind::Layout(layout) => Arc::new(LayoutGenerator::new(layout)),
+        }
+    }
+
+    /// Checks that a world can start in `topology`. Only layouts can fail,
+    /// by having mines next to every place to start.
+    pub fn check(&self, topology: Topology) -> Result<(), String> {
+        match self {
+            GeneratorKind::Layout(layout) => layout.check(topology),
+            _ => Ok(()),
+        }
+    }
+
+    /// Number used for the generator in world codes. Layouts can't be
+    /// shared as a code.
+    pub fn code(&self) -> Option<u8> {
+        match self {
+            GeneratorKind::Uniform    => Some(0),
+            GeneratorKind::Clustered  => Some(1),
+            GeneratorKind::Noise      => Some(2),
+            GeneratorKind::Stratified => Some(3),
+            GeneratorKind::Layout(_)  => None,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<GeneratorKind> {
+        match code {
+            0 => Some(GeneratorKind::Uniform),
+            1 => Some(GeneratorKind::Clustered),
+            2 => Some(GeneratorKind::Noise),
+            3 => Some(GeneratorKind::Stratified),
+            _ => None,
+        }
+    }
+}
+
+/// Parses `--generator`: the name of a generator, or else a file with a
+/// layout.
+pub fn parse_generator(text: &str) -> Result<GeneratorKind, String> {
+    Ok(match text {
+        "uniform"    => GeneratorKind::Uniform,
+        "clustered"  => GeneratorKind::Clustered,
+        "noise"      => GeneratorKind::Noise,
+        "stratified" => GeneratorKind::Stratified,
+        path => {
+            let layout = fs::read_to_string(path).map_err(|error| {
+                format!("`{path}` is not uniform, clustered, noise, stratified or a readable file: {error}")
+            })?;
+            GeneratorKind::Layout(Layout::parse(&layout)?)
+        },
+    })
+}
+
+#[derive(Debug)]
+struct Uniform;
+
+impl Generator for Uniform {
+    fn value(&self, seed: u64, place: PlaceI32, mine_concentration: f64) -> CellValue {
+        mine_if(unit(hash(seed, place, 0)) < mine_concentration)
+    }
+}
+
+/// `Uniform` as of version 0, which is slow as it seeds a generator for
+/// every cell. This was `rand`'s `StdRng`, which is free to change what
+/// it is, so ChaCha12 is named instead, along with how its output
+/// became a number from 0 up to 1.
+#[derive(Debug)]
+struct SeededUniform;
+
+impl Generator for SeededUniform {
+    fn value(&self, seed_u64: u64, place: PlaceI32, mine_concentration: f64) -> CellValue {
+        let mut seed = [42; 32];
+        seed[ 0..8 ].copy_from_slice(&seed_u64.to_be_bytes());
+        seed[ 8..12].copy_from_slice(&place.x .to_be_bytes());
+        seed[12..16].copy_from_slice(&place.y .to_be_bytes());
+
+        let mut rng = ChaCha12Rng::from_seed(seed);
+
+        if mine_concentration > unit(rng.next_u64()) {
+            CellValue::Mine
+        } else {
+            CellValue::Empty
+        }
+    }
+}
+
+/// Mines are likely near cluster centres scattered over the world, one
+/// in each square of `SPACING` cells, and rare elsewhere.
+#[derive(Debug)]
+struct Clustered;
+
+impl Clustered {
+    const SPACING: i32 = 16;
+    /// standard deviation of the bell curve around each centre
+    const SPREAD: f64 = 2.5;
+    /// part of the mines that are spread evenly between clusters
+    const BACKGROUND: f64 = 0.2;
+}
+
+impl Generator for Clustered {
+    fn value(&self, seed: u64, place: PlaceI32, mine_concentration: f64) -> CellValue {
+        let square = PlaceI32 {
+            x: place.x.div_euclid(Self::SPACING),
+            y: place.y.div_euclid(Self::SPACING),
+        };
+        let mut density = 0f64;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                let square = PlaceI32 { x: square.x + dx, y: square.y + dy };
+                // in f64, as the squares past the edges of the world start beyond i32
+                let spacing = Self::SPACING as f64;
+                let centre_x = square.x as f64 * spacing + unit(hash(seed, square, 1)) * spacing;
+                let centre_y = square.y as f64 * spacing + unit(hash(seed, square, 2)) * spacing;
+                let distance_squared = (place.x as f64 - centre_x).powi(2) + (place.y as f64 - centre_y).powi(2);
+                density += (-distance_squared / (2f64 * Self::SPREAD * Self::SPREAD)).exp();
+            }
+        }
+        // the average density over the world is the area under one bell curve per square
+        let mean_density = 2f64 * PI * Self::SPREAD * Self::SPREAD / (Self::SPACING * Self::SPACING) as f64;
+        let probability = mine_concentration
+            * (Self::BACKGROUND + (1f64 - Self::BACKGROUND) * density / mean_density);
+        mine_if(unit(hash(seed, place, 3)) < probability)
+    }
+}
+
+/// The probability of a mine follows smooth value noise, from nothing
+/// to twice the mine concentration.
+#[derive(Debug)]
+struct Noise;
+
+impl Noise {
+    /// distance between the points the noise is interpolated between
+    const SCALE: i32 = 12;
+}
+
+impl Generator for Noise {
+    fn value(&self, seed: u64, place: PlaceI32, mine_concentration: f64) -> CellValue {
+        let corner = PlaceI32 {
+            x: place.x.div_euclid(Self::SCALE),
+            y: place.y.div_euclid(Self::SCALE),
+        };
+        let smooth = |fraction: f64| fraction * fraction * (3f64 - 2f64 * fraction);
+        let tx = smooth(place.x.rem_euclid(Self::SCALE) as f64 / Self::SCALE as f64);
+        let ty = smooth(place.y.rem_euclid(Self::SCALE) as f64 / Self::SCALE as f64);
+        let lattice = |dx: i32, dy: i32| unit(hash(seed, PlaceI32 { x: corner.x + dx, y: corner.y + dy }, 4));
+        let bottom = lattice(0, 0) * (1f64 - tx) + lattice(1, 0) * tx;
+        let top    = lattice(0, 1) * (1f64 - tx) + lattice(1, 1) * tx;
+        let noise  = bottom * (1f64 - ty) + top * ty;
+        // the noise averages one half
+        let probability = 2f64 * mine_concentration * noise;
+        mine_if(unit(hash(seed, place, 5)) < probability)
+    }
+}
+
+/// Every block has the number of mines the concentration asks for, so
+/// there are no unusually dense or empty areas.
+#[derive(Debug)]
+struct Stratified;
+
+impl Stratified {
+    const BLOCK: i32 = 8;
+}
+
+impl Generator for Stratified {
+    fn value(&self, seed: u64, place: PlaceI32, mine_concentration: f64) -> CellValue {
+        let block = PlaceI32 {
+            x: place.x.div_euclid(Self::BLOCK),
+            y: place.y.div_euclid(Self::BLOCK),
+        };
+        // with a density curve the concentration differs slightly across a
+        // block, so its cells may not quite agree on the number of mines
+        let expected = mine_concentration * (Self::BLOCK * Self::BLOCK) as f64;
+        // the fraction of a mine left over is placed in some blocks and not others
+        let mine_count = expected.floor() as usize
+            + (unit(hash(seed, block, 6)) < expected.fract()) as usize;
+        // the mines are the cells with the lowest keys in the block
+        let key = hash(seed, place, 7);
+        let corner = PlaceI32 { x: block.x * Self::BLOCK, y: block.y * Self::BLOCK };
+        let mut lower = 0;
+        // offsets from the corner, as the block's far edge may be past i32
+        for dy in 0..Self::BLOCK {
+            for dx in 0..Self::BLOCK {
+                if hash(seed, PlaceI32 { x: corner.x + dx, y: corner.y + dy }, 7) < key {
+                    lower += 1;
+                }
+            }
+        }
+        mine_if(lower < mine_count)
+    }
+}
+
+/// Mines drawn as text: `*` is a mine and any other character is not.
+/// The first line is the top row.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Layout {
+    rows: Vec<String>,
+}
+
+impl Layout {
+    pub fn parse(text: &str) -> Result<Layout, String> {
+        let rows: Vec<String> = text.lines().map(|row| row.trim_end().to_string()).collect();
+        let height = rows.iter().rposition(|row| !row.is_empty()).map_or(0, |last| last + 1);
+        let rows = rows[..height].to_vec();
+        if rows.iter().all(|row| row.is_empty()) {
+            return Err("layout is empty".to_string());
+        }
+        Ok(Layout { rows })
+    }
+
+    /// Checks that there is a place to start with no mines in the cells
+    /// `topology` counts around it. Parsing can't check this, as the
+    /// topology is picked separately.
+    pub fn check(&self, topology: Topology) -> Result<(), String> {
+        // the layout repeats, so every place to start is within one copy of it
+        let generator = LayoutGenerator::new(self);
+        let start = PlaceI32::ORIGIN;
+        let clear = |origin: PlaceI32| std::iter::once(start).chain(topology.neighbours(start)).all(|place| {
+            let place = PlaceI32 { x: origin.x + place.x, y: origin.y + place.y };
+            matches!(generator.value(0, place, 0f64), CellValue::Empty)
+        });
+        if !(0..generator.width).any(|x| (0..generator.height).any(|y| clear(PlaceI32 { x, y }))) {
+            return Err("layout has no place to start without mines around it".to_string());
+        }
+        Ok(())
+    }
+
+    fn width(&self) -> usize {
+        self.rows.iter().map(|row| row.chars().count()).max().unwrap_or(0)
+    }
+
+    /// Fraction of the layout that is mines.
+    pub fn mine_concentration(&self) -> f64 {
+        let mines: usize = self.rows.iter().map(|row| row.matches('*').count()).sum();
+        mines as f64 / (self.width() * self.rows.len()) as f64
+    }
+}
+
+#[derive(Debug)]
+struct LayoutGenerator {
+    width: i32,
+    height: i32,
+    /// by row from the bottom, then by column
+    mines: Vec<bool>,
+}
+
+impl LayoutGenerator {
+    fn new(layout: &Layout) -> LayoutGenerator {
+        let width = layout.width();
+        let mines = layout.rows.iter().rev()
+            .flat_map(|row| (0..width).map(|x| row.chars().nth(x) == Some('*')))
+            .collect();
+        LayoutGenerator { width: width as i32, height: layout.rows.len() as i32, mines }
+    }
+}
+
+impl Generator for LayoutGenerator {
+    fn value(&self, _seed: u64, place: PlaceI32, _mine_concentration: f64) -> CellValue {
+        let x = place.x.rem_euclid(self.width);
+        let y = place.y.rem_euclid(self.height);
+        mine_if(self.mines[(y * self.width + x) as usize])
+    }
+}
+
+fn mine_if(mine: bool) -> CellValue {
+    if mine { CellValue::Mine } else { CellValue::Empty }
+}
+
+/// Mixes a seed, a place and a number telling uses apart, a step of
+/// splitmix64 at a time.
+fn hash(seed: u64, place: PlaceI32, salt: u64) -> u64 {
+    [salt, place.x as u32 as u64, place.y as u32 as u64].into_iter().fold(seed, |hash, part| {
+        let mut hash = hash.wrapping_add(0x9e37_79b9_7f4a_7c15) ^ part;
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        hash ^ (hash >> 31)
+    })
+}
+
+/// A number from 0 up to 1.
+fn unit(hash: u64) -> f64 {
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
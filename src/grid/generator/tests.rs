@@ -0,0 +1,145 @@
+//! Golden layouts of known worlds. If one of these fails, a change made
+//! existing seeds give different worlds: it must come as a new version
+//! in `GeneratorKind::VERSION` instead. The last tests check layouts
+//! and the edges of the world.
+
+use super::{GeneratorKind, Layout};
+use crate::grid::cell::CellValue;
+use crate::grid::cell_builder::CellBuilder;
+use crate::grid::topology::Topology;
+use crate::helper::PlaceI32;
+
+const MINE_CONCENTRATION: f64 = 0.2;
+
+/// Mines from -8,-4 to 7,3 of a new world, top row first, which also
+/// pins where the start is found.
+fn layout(seed: u64, generator: GeneratorKind, version: u8) -> String {
+    let cell_builder = CellBuilder::new(
+        MINE_CONCENTRATION, Some(seed), &generator, version, None, Topology::Square,
+        |message: &'static str| panic!("{}", message),
+    );
+    let rows: Vec<String> = (-4..4).rev()
+        .map(|y| (-8..8).map(|x| match cell_builder.cell(PlaceI32 { x, y }).value {
+            CellValue::Mine  => '*',
+            CellValue::Empty => '.',
+        }).collect())
+        .collect();
+    rows.join("\n")
+}
+
+#[test]
+fn version_0_uniform() {
+    assert_eq!(layout(1, GeneratorKind::Uniform, 0), "\
+....*.....***...
+.*.*..**....*...
+.....*..........
+...*......*.....
+*...............
+*...*..*........
+.**...*......**.
+...**...**......");
+    assert_eq!(layout(u64::MAX, GeneratorKind::Uniform, 0), "\
+**.........*...*
+*......*......**
+................
+..........**..*.
+..*.............
+.*.....*........
+..*.....*.......
+.*..*.*..*.**...");
+}
+
+#[test]
+fn version_1_uniform() {
+    assert_eq!(layout(1, GeneratorKind::Uniform, 1), "\
+......**.*.*....
+*.**...........*
+.***.*.........*
+**..............
+..*............*
+.*.....*........
+........*..*....
+......**....*...");
+    assert_eq!(layout(u64::MAX, GeneratorKind::Uniform, 1), "\
+*..*.........*..
+.*...*......*...
+....*.*.........
+.*..............
+....*...........
+.......*....*...
+..*...**......*.
+*...............");
+}
+
+#[test]
+fn version_1_clustered() {
+    assert_eq!(layout(1, GeneratorKind::Clustered, 1), "\
+....*...........
+................
+..............*.
+....*...........
+...*..*.........
+...**.**...*....
+.....*****.*....
+....*.****.*.*..");
+}
+
+#[test]
+fn version_1_noise() {
+    assert_eq!(layout(1, GeneratorKind::Noise, 1), "\
+**..**......*...
+*......*........
+......*.....*...
+.****...........
+**.*............
+.......*....*.*.
+**....*.........
+.*.*...*......*.");
+}
+
+#[test]
+fn version_1_stratified() {
+    assert_eq!(layout(1, GeneratorKind::Stratified, 1), "\
+..**..*......*.*
+........*..*....
+.**.............
+*....**...*....*
+...*..*.........
+*...***.......*.
+*.......*.......
+........*.*...*.");
+}
+
+#[test]
+fn layouts_need_a_start_clear_in_their_topology() {
+    // a mine in every fifth column leaves room for 3x3 squares, but not
+    // for anything reaching two cells to each side
+    let layout = GeneratorKind::Layout(Layout::parse("*....").unwrap());
+    for topology in [Topology::Square, Topology::Hex, Topology::Orthogonal] {
+        assert_eq!(layout.check(topology), Ok(()), "{topology:?}");
+    }
+    for topology in [Topology::Knight, Topology::Radius2] {
+        assert!(layout.check(topology).is_err(), "{topology:?}");
+    }
+    let layout = GeneratorKind::Layout(Layout::parse("*").unwrap());
+    assert!(layout.check(Topology::Orthogonal).is_err());
+    assert!(Layout::parse("\n  \n").is_err());
+}
+
+#[test]
+fn generators_work_at_the_edges_of_the_world() {
+    let layout = GeneratorKind::Layout(Layout::parse("*....").unwrap());
+    let generators = [
+        GeneratorKind::Uniform, GeneratorKind::Clustered, GeneratorKind::Noise, GeneratorKind::Stratified, layout,
+    ];
+    for generator in generators {
+        for version in 0..=GeneratorKind::VERSION {
+            let generator = generator.generator(version);
+            for x in [i32::MIN, i32::MIN + 1, -1, 0, i32::MAX - 1, i32::MAX] {
+                for y in [i32::MIN, i32::MIN + 1, -1, 0, i32::MAX - 1, i32::MAX] {
+                    generator.value(1, PlaceI32 { x, y }, MINE_CONCENTRATION);
+                }
+            }
+        }
+    }
+}
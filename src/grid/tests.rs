@@ -0,0 +1,207 @@
+use super::Grid;
+use super::cell::{CellState, CellValue};
+use super::cell_builder::CellBuilder;
+use super::generator::{GeneratorKind, Layout};
+use super::topology::Topology;
+use crate::helper::{PlaceI32, RectI32, SizeI32};
+
+const SEED: u64 = 1;
+const MINE_CONCENTRATION: f64 = 0.2;
+const VERSION: u8 = 1;
+
+fn grid() -> Grid {
+    Grid::new(CellBuilder::new(
+        MINE_CONCENTRATION, Some(SEED), &GeneratorKind::Uniform, VERSION, None, Topology::Square,
+        |message: &'static str| panic!("{}", message),
+    ))
+}
+
+fn rect(x: i32, y: i32, width: i32, height: i32) -> RectI32 {
+    RectI32 { corner: PlaceI32 { x, y }, size: SizeI32 { width, height } }
+}
+
+fn sorted(places: impl Iterator<Item = PlaceI32>) -> Vec<(i32, i32)> {
+    let mut places: Vec<(i32, i32)> = places.map(|place| (place.x, place.y)).collect();
+    places.sort();
+    places
+}
+
+/// Places of empty cells with at least one mine next to them, which
+/// show a number when revealed.
+fn numbered_places(grid: &Grid) -> impl Iterator<Item = PlaceI32> + '_ {
+    (0..).map(|x| PlaceI32 { x, y: 0 })
+        .filter(|&place| matches!(grid.peek(place).value, CellValue::Empty) && grid.mine_count(place) > 0)
+}
+
+#[test]
+fn peeking_stores_nothing() {
+    let grid = grid();
+    for y in -200..200 {
+        for x in -200..200 {
+            let place = PlaceI32 { x, y };
+            grid.peek(place);
+            grid.peek_state(place);
+            grid.mine_count(place);
+            assert!(grid.peek_stored(place).is_none());
+        }
+    }
+    assert_eq!(grid.memory_usage(), 0);
+    assert_eq!(grid.cells().count(), 0);
+}
+
+#[test]
+fn an_unchanged_cell_is_not_stored() {
+    let mut grid = grid();
+    let place = PlaceI32 { x: 5, y: 5 };
+    // borrowing without changing the state stores nothing
+    drop(grid.get_mut(place));
+    assert_eq!(grid.memory_usage(), 0);
+    grid.get_mut(place).flag();
+    assert!(grid.memory_usage() > 0);
+}
+
+#[test]
+fn unflagging_forgets_the_cell() {
+    let mut grid = grid();
+    let place = PlaceI32 { x: 5, y: 5 };
+    grid.get_mut(place).flag();
+    grid.get_mut(place).unflag();
+    assert_eq!(grid.cells().count(), 0);
+    assert!(grid.peek_stored(place).is_none());
+    assert_eq!(grid.chunks.len(), 0);
+}
+
+#[test]
+fn mine_counts_are_kept_through_changes() {
+    let mut grid = grid();
+    let place = numbered_places(&grid).next().unwrap();
+    let expected = grid.mine_count(place);
+
+    grid.get_mut(place).flag();
+    assert_eq!(grid.peek_stored(place).unwrap().1, None);
+    grid.get_mut(place).unflag();
+    grid.get_mut(place).reveal();
+    assert_eq!(grid.peek_stored(place).unwrap().1, Some(expected));
+
+    // other cells of the chunk changing leaves the count alone
+    let neighbours: Vec<PlaceI32> = grid.neighbours(place).collect();
+    for &neighbour in &neighbours {
+        grid.get_mut(neighbour).flag();
+    }
+    for &neighbour in &neighbours {
+        grid.get_mut(neighbour).unflag();
+    }
+    let empty = neighbours.iter().copied()
+        .find(|&neighbour| matches!(grid.peek(neighbour).value, CellValue::Empty))
+        .unwrap();
+    grid.get_mut(empty).reveal();
+    assert_eq!(grid.peek_stored(place).unwrap().1, Some(expected));
+
+    // as when a host's cell changes are copied to a client
+    grid.get_mut(place).state = CellState::Hidden;
+    grid.get_mut(place).state = CellState::Revealed;
+    assert_eq!(grid.peek_stored(place).unwrap().1, Some(expected));
+    assert_eq!(grid.mine_count(place), expected);
+}
+
+#[test]
+fn cells_are_the_changed_ones() {
+    let mut grid = grid();
+    let places = [PlaceI32 { x: -40, y: 3 }, PlaceI32 { x: 0, y: 0 }, PlaceI32 { x: 70, y: -90 }];
+    for place in places {
+        grid.get_mut(place).flag();
+    }
+    grid.get_mut(PlaceI32 { x: 1, y: 1 }).flag();
+    grid.get_mut(PlaceI32 { x: 1, y: 1 }).unflag();
+    assert_eq!(sorted(grid.cells().map(|(place, _)| place)), sorted(places.into_iter()));
+    assert!(grid.cells().all(|(_, cell)| cell.state == CellState::Flagged));
+}
+
+#[test]
+fn cells_in_finds_the_same_cells_either_way() {
+    let mut grid = grid();
+    // cells in many chunks, so small areas walk the chunks they cover
+    // and large ones go through the stored chunks
+    for index in -20..20 {
+        grid.get_mut(PlaceI32 { x: index * 13, y: index * 7 }).flag();
+    }
+    for area in [
+        rect(0, 0, 1, 1),
+        rect(-30, -20, 40, 30),
+        rect(-1000, -1000, 2000, 2000),
+        rect(-260, -140, 261, 1),
+        rect(5, 5, 0, 10),
+    ] {
+        let expected = sorted(grid.cells().map(|(place, _)| place).filter(|&place| area.contains(place)));
+        assert_eq!(sorted(grid.cells_in(area).map(|(place, _)| place)), expected, "{area:?}");
+    }
+}
+
+#[test]
+fn cells_in_handles_the_edges_of_the_world() {
+    const EDGE: i32 = Grid::MAX_COORDINATE;
+    let mut grid = grid();
+    for place in [PlaceI32 { x: EDGE, y: EDGE }, PlaceI32 { x: -EDGE, y: -EDGE }] {
+        grid.get_mut(place).flag();
+    }
+    let area = rect(EDGE - 5, EDGE - 5, 2000, 2000);
+    assert_eq!(sorted(grid.cells_in(area).map(|(place, _)| place)), [(EDGE, EDGE)]);
+    let area = rect(i32::MIN, i32::MIN, i32::MAX, i32::MAX);
+    assert_eq!(sorted(grid.cells_in(area).map(|(place, _)| place)), [(-EDGE, -EDGE)]);
+}
+
+#[test]
+fn counts_follow_changes() {
+    let mut grid = grid();
+    let place = numbered_places(&grid).next().unwrap();
+    let other = numbered_places(&grid).nth(1).unwrap();
+    assert_eq!((grid.revealed_count(), grid.flag_count()), (0, 0));
+    grid.get_mut(place).flag();
+    grid.get_mut(other).flag();
+    assert_eq!((grid.revealed_count(), grid.flag_count()), (0, 2));
+    grid.get_mut(place).unflag();
+    assert_eq!((grid.revealed_count(), grid.flag_count()), (0, 1));
+    grid.get_mut(place).reveal();
+    // revealing a flagged cell does nothing
+    grid.get_mut(other).reveal();
+    assert_eq!((grid.revealed_count(), grid.flag_count()), (1, 1));
+    grid.get_mut(other).state = CellState::Revealed;
+    assert_eq!((grid.revealed_count(), grid.flag_count()), (2, 0));
+    grid.get_mut(other).state = CellState::Hidden;
+    assert_eq!((grid.revealed_count(), grid.flag_count()), (1, 0));
+}
+
+#[test]
+fn bounding_box_surrounds_the_changed_cells() {
+    let mut grid = grid();
+    assert_eq!(grid.bounding_box(), None);
+    grid.get_mut(PlaceI32 { x: 3, y: -2 }).flag();
+    assert_eq!(grid.bounding_box(), Some(rect(3, -2, 1, 1)));
+    grid.get_mut(PlaceI32 { x: -40, y: 100 }).flag();
+    grid.get_mut(PlaceI32 { x: 10, y: 0 }).flag();
+    assert_eq!(grid.bounding_box(), Some(rect(-40, -2, 51, 103)));
+    // cells changed back no longer count
+    grid.get_mut(PlaceI32 { x: -40, y: 100 }).unflag();
+    assert_eq!(grid.bounding_box(), Some(rect(3, -2, 8, 3)));
+}
+
+#[test]
+fn cells_can_be_built_at_the_edges_of_the_world() {
+    let generators = [
+        GeneratorKind::Uniform,
+        GeneratorKind::Clustered,
+        GeneratorKind::Noise,
+        GeneratorKind::Stratified,
+        GeneratorKind::Layout(Layout::parse("*....").unwrap()),
+    ];
+    for generator in &generators {
+        let cell_builder = CellBuilder::new(
+            MINE_CONCENTRATION, Some(SEED), generator, VERSION, None, Topology::Square,
+            |message: &'static str| panic!("{}", message),
+        );
+        for (x, y) in [(i32::MAX, i32::MAX), (i32::MIN, i32::MIN), (i32::MAX, i32::MIN), (i32::MIN, i32::MAX)] {
+            let cell = cell_builder.cell(PlaceI32 { x, y });
+            assert!(matches!(cell.state, CellState::Hidden));
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use super::PlaceI32;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod tests;
+
+/// How cells fit together and which of them a number counts. Each
+/// variant pairs a `Tiling` with a `Neighbourhood`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Topology {
+    /// squares with eight neighbours
+    #[default]
+    Square,
+    /// hexagons with six neighbours, in rows where every odd row sits
+    /// half a cell to the right
+    Hex,
+    /// squares counting the eight cells a knight's move away
+    Knight,
+    /// squares counting the 24 others in the 5x5 square around them
+    Radius2,
+    /// squares counting only the four cells sharing an edge
+    Orthogonal,
+}
+
+/// Shape of the cells and how their rows line up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tiling {
+    /// squares in straight rows and columns
+    Square,
+    /// hexagons in rows where every odd row sits half a cell to the right
+    Hex,
+}
+
+/// Which cells around a cell its number counts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// every cell it touches
+    Touching,
+    /// the cells a knight's move away
+    Knight,
+    /// every other cell of the 5x5 square around it
+    Radius2,
+    /// the cells sharing an edge
+    Orthogonal,
+}
+
+impl Topology {
+    const SQUARE: [(i32, i32); 8] = [
+        (-1, -1), (-1, 0), (-1, 1),
+        ( 0, -1),          ( 0, 1),
+        ( 1, -1), ( 1, 0), ( 1, 1),
+    ];
+    const KNIGHT: [(i32, i32); 8] = [
+        (-2, -1), (-2, 1), (-1, -2), (-1, 2),
+        ( 1, -2), ( 1, 2), ( 2, -1), ( 2, 1),
+    ];
+    const RADIUS_2: [(i32, i32); 24] = [
+        (-2, -2), (-2, -1), (-2, 0), (-2, 1), (-2, 2),
+        (-1, -2), (-1, -1), (-1, 0), (-1, 1), (-1, 2),
+        ( 0, -2), ( 0, -1),          ( 0, 1), ( 0, 2),
+        ( 1, -2), ( 1, -1), ( 1, 0), ( 1, 1), ( 1, 2),
+        ( 2, -2), ( 2, -1), ( 2, 0), ( 2, 1), ( 2, 2),
+    ];
+    const ORTHOGONAL: [(i32, i32); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+    /// Most mines a number can count, in any topology.
+    pub const MAX_MINE_COUNT: u8 = Self::RADIUS_2.len() as u8;
+
+    pub fn tiling(self) -> Tiling {
+        match self {
+            Topology::Hex => Tiling::Hex,
+            Topology::Square | Topology::Knight | Topology::Radius2 | Topology::Orthogonal => Tiling::Square,
+        }
+    }
+
+    pub fn neighbourhood(self) -> Neighbourhood {
+        match self {
+            Topology::Square | Topology::Hex => Neighbourhood::Touching,
+            Topology::Knight     => Neighbourhood::Knight,
+            Topology::Radius2    => Neighbourhood::Radius2,
+            Topology::Orthogonal => Neighbourhood::Orthogonal,
+        }
+    }
+
+    pub fn neighbours(self, place: PlaceI32) -> impl Iterator<Item = PlaceI32> {
+        let offsets: &'static [(i32, i32)] = match (self.tiling(), self.neighbourhood()) {
+            // hexagons only count the cells they touch
+            (Tiling::Hex, _) => Tiling::hex_row(place.y),
+            (Tiling::Square, Neighbourhood::Touching)   => &Self::SQUARE,
+            (Tiling::Square, Neighbourhood::Knight)     => &Self::KNIGHT,
+            (Tiling::Square, Neighbourhood::Radius2)    => &Self::RADIUS_2,
+            (Tiling::Square, Neighbourhood::Orthogonal) => &Self::ORTHOGONAL,
+        };
+        offsets.iter().map(move |&(x, y)| PlaceI32 { x: place.x + x, y: place.y + y })
+    }
+
+    /// How many cells away along x or y the furthest neighbour is.
+    pub fn reach(self) -> i32 {
+        match self.neighbourhood() {
+            Neighbourhood::Knight | Neighbourhood::Radius2 => 2,
+            Neighbourhood::Touching | Neighbourhood::Orthogonal => 1,
+        }
+    }
+
+    /// Number used for the topology in world codes.
+    pub fn code(self) -> u8 {
+        match self {
+            Topology::Square     => 0,
+            Topology::Hex        => 1,
+            Topology::Knight     => 2,
+            Topology::Radius2    => 3,
+            Topology::Orthogonal => 4,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Topology> {
+        match code {
+            0 => Some(Topology::Square),
+            1 => Some(Topology::Hex),
+            2 => Some(Topology::Knight),
+            3 => Some(Topology::Radius2),
+            4 => Some(Topology::Orthogonal),
+            _ => None,
+        }
+    }
+}
+
+impl Tiling {
+    const HEX_EVEN_ROW: [(i32, i32); 6] = [(-1, 0), (1, 0), (-1, 1), (0, 1), (-1, -1), (0, -1)];
+    const HEX_ODD_ROW:  [(i32, i32); 6] = [(-1, 0), (1, 0), ( 0, 1), (1, 1), ( 0, -1), (1, -1)];
+
+    /// Offsets of the hexagons touching one in row `y`.
+    fn hex_row(y: i32) -> &'static [(i32, i32)] {
+        if Tiling::Hex.shifted(y) { &Self::HEX_ODD_ROW } else { &Self::HEX_EVEN_ROW }
+    }
+
+    /// Whether row `y` is drawn half a cell to the right.
+    pub fn shifted(self, y: i32) -> bool {
+        match self {
+            Tiling::Square => false,
+            Tiling::Hex => y.rem_euclid(2) == 1,
+        }
+    }
+
+    /// The cell above or below `place` on its left or right side, which on
+    /// hexagons is the neighbour sharing that edge.
+    pub fn diagonal(self, place: PlaceI32, up: bool, right: bool) -> PlaceI32 {
+        let (x, y) = match self {
+            Tiling::Square => (if right { 1 } else { -1 }, if up { 1 } else { -1 }),
+            // after left and right, the rows list up left, up right, down left, down right
+            Tiling::Hex => Self::hex_row(place.y)[2 + if up { 0 } else { 2 } + right as usize],
+        };
+        PlaceI32 { x: place.x + x, y: place.y + y }
+    }
+}
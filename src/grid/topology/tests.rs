@@ -0,0 +1,34 @@
+use super::{Tiling, Topology};
+use crate::helper::PlaceI32;
+
+#[test]
+fn hex_diagonals_with_left_and_right_are_the_neighbours() {
+    for place in [PlaceI32 { x: 3, y: 4 }, PlaceI32 { x: 3, y: 5 }, PlaceI32 { x: -2, y: -1 }] {
+        let mut steps = vec![PlaceI32 { x: place.x - 1, ..place }, PlaceI32 { x: place.x + 1, ..place }];
+        for up in [true, false] {
+            for right in [false, true] {
+                steps.push(Tiling::Hex.diagonal(place, up, right));
+            }
+        }
+        let neighbours: Vec<PlaceI32> = Topology::Hex.neighbours(place).collect();
+        assert_eq!(steps, neighbours, "{place:?}");
+    }
+}
+
+#[test]
+fn hex_diagonals_lean_the_way_they_say() {
+    // odd rows sit half a cell to the right of even rows
+    let even = PlaceI32 { x: 0, y: 0 };
+    assert_eq!(Tiling::Hex.diagonal(even, true,  false), PlaceI32 { x: -1, y: 1 });
+    assert_eq!(Tiling::Hex.diagonal(even, true,  true),  PlaceI32 { x:  0, y: 1 });
+    let odd = PlaceI32 { x: 0, y: 1 };
+    assert_eq!(Tiling::Hex.diagonal(odd, false, false), PlaceI32 { x: 0, y: 0 });
+    assert_eq!(Tiling::Hex.diagonal(odd, false, true),  PlaceI32 { x: 1, y: 0 });
+}
+
+#[test]
+fn square_diagonals_are_the_corners() {
+    let place = PlaceI32 { x: 3, y: 4 };
+    assert_eq!(Tiling::Square.diagonal(place, true,  false), PlaceI32 { x: 2, y: 5 });
+    assert_eq!(Tiling::Square.diagonal(place, false, true),  PlaceI32 { x: 4, y: 3 });
+}
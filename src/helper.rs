@@ -1,6 +1,6 @@
-use std::ops::Deref;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PlaceI32 {
     pub x: i32,
     pub y: i32,
@@ -9,14 +9,6 @@ pub struct PlaceI32 {
 impl PlaceI32 {
     pub const ORIGIN: PlaceI32 = PlaceI32 { x: 0, y: 0 };
 
-    /// Radius of the smallest tile containing the place.
-    pub fn radius(&self) -> i32 {
-        std::cmp::max(
-            if self.x >= 0 { self.x + 1 } else { self.x.abs() },
-            if self.y >= 0 { self.y + 1 } else { self.y.abs() },
-        )
-    }
-
     // pub fn within(&self, place: PlaceI32, area: SizeI32) -> bool {
     //     if ((place.x - area.width  / 2)..(place.x + area.width  / 2)).contains(&self.x) &&
     //        ((place.y - area.height / 2)..(place.y + area.height / 2)).contains(&self.y) {
@@ -27,12 +19,57 @@ impl PlaceI32 {
     // }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SizeI32 {
     pub width:  i32,
     pub height: i32,
 }
 
+/// Rectangle of places, spanning from `corner` (bottom left) and up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RectI32 {
+    pub corner: PlaceI32,
+    pub size:   SizeI32,
+}
+
+impl RectI32 {
+    /// Square with sides `2 * radius + 1` centered on `center`.
+    pub fn around(center: PlaceI32, radius: i32) -> RectI32 {
+        RectI32 {
+            corner: PlaceI32 { x: center.x - radius, y: center.y - radius },
+            size:   SizeI32  { width: radius * 2 + 1, height: radius * 2 + 1 },
+        }
+    }
+
+    pub fn grow(&self, margin: i32) -> RectI32 {
+        RectI32 {
+            corner: PlaceI32 { x: self.corner.x - margin, y: self.corner.y - margin },
+            size: SizeI32 {
+                width:  self.size.width  + margin * 2,
+                height: self.size.height + margin * 2,
+            },
+        }
+    }
+
+    pub fn contains(&self, place: PlaceI32) -> bool {
+        // in i64, so rectangles reaching the edge of i32 don't overflow
+        let within = |start: i32, length: i32, value: i32| {
+            (start as i64..start as i64 + length as i64).contains(&(value as i64))
+        };
+        within(self.corner.x, self.size.width,  place.x) &&
+        within(self.corner.y, self.size.height, place.y)
+    }
+
+    pub fn places(&self) -> impl Iterator<Item = PlaceI32> + use<> {
+        let RectI32 { corner, size } = *self;
+        (corner.y..corner.y + size.height).flat_map(
+            move |y| (corner.x..corner.x + size.width).map(
+                move |x| PlaceI32 { x, y },
+            ),
+        )
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct PlaceUsize {
     pub x: usize,
@@ -45,27 +82,11 @@ pub struct SizeUsize {
     pub height: usize,
 }
 
-impl Into<SizeI32> for SizeUsize {
-    fn into(self) -> SizeI32 {
+impl From<SizeUsize> for SizeI32 {
+    fn from(size: SizeUsize) -> SizeI32 {
         SizeI32 {
-            width:  self.width  as i32,
-            height: self.height as i32,
+            width:  size.width  as i32,
+            height: size.height as i32,
         }
     }
-}
-
-pub struct Immut<T>(T);
-
-impl<T> Immut<T> {
-    pub fn new(value: T) -> Immut<T> {
-        Immut(value)
-    }
-}
-
-impl<T> Deref for Immut<T> {
-    type Target = T;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
 }
\ No newline at end of file
@@ -2,6 +2,7 @@ pub mod helper;
 pub mod grid;
 pub mod view;
 pub mod game;
+pub mod solver;
 
 use game::Game;
 
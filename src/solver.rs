@@ -0,0 +1,350 @@
+use self::constraint::Constraint;
+use self::enumeration::Enumeration;
+use crate::game::Game;
+use crate::grid::Grid;
+use crate::grid::cell::{CellState, CellValue};
+use crate::helper::{PlaceI32, RectI32};
+use std::collections::HashMap;
+
+mod constraint;
+mod enumeration;
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Safe,
+    Mine,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Rule {
+    /// a single number accounts for all of its hidden neighbours
+    Single,
+    /// the difference between two overlapping numbers
+    Pair,
+    /// every arrangement consistent with the numbers agrees
+    Enumeration,
+}
+
+/// A revealed number.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Source {
+    pub place: PlaceI32,
+    pub number: u8,
+}
+
+fn merge_sources(into: &mut Vec<Source>, sources: &[Source]) {
+    for &source in sources {
+        if !into.contains(&source) {
+            into.push(source);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Finding {
+    pub place: PlaceI32,
+    pub verdict: Verdict,
+    pub rule: Rule,
+    /// revealed numbers that imply the verdict
+    pub sources: Vec<Source>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Hint {
+    Certain(Finding),
+    /// no certain move is known; this is the least risky cell to reveal
+    Guess {
+        place: PlaceI32,
+        probability: f64,
+    },
+}
+
+impl Hint {
+    pub fn place(&self) -> PlaceI32 {
+        match self {
+            Hint::Certain(finding) => finding.place,
+            Hint::Guess { place, .. } => *place,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Analysis {
+    pub findings: Vec<Finding>,
+    /// hidden cells adjacent to a revealed number, in scan order
+    pub frontier: Vec<PlaceI32>,
+    probabilities: HashMap<PlaceI32, f64>,
+}
+
+impl Analysis {
+    pub fn finding(&self, place: PlaceI32) -> Option<&Finding> {
+        self.findings.iter().find(|finding| finding.place == place)
+    }
+
+    /// Probability that a frontier cell is a mine. Cells that are
+    /// not on the frontier are not constrained by any number.
+    pub fn mine_probability(&self, place: PlaceI32) -> Option<f64> {
+        self.probabilities.get(&place).copied()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Knowledge {
+    Hidden,
+    Flagged,
+    Mine,
+    /// a revealed number
+    Clear,
+}
+
+/// Deduces safe cells and mines from what the player can see. Only the
+/// state of hidden cells is ever read, never their value.
+#[derive(Debug)]
+pub struct Solver<'a> {
+    grid: &'a Grid,
+    mine_concentration: f64,
+}
+
+impl<'a> Solver<'a> {
+    /// Upper bound on search steps spent enumerating one frontier component.
+    pub const MAX_ENUMERATION_STEPS: u32 = 1 << 20;
+
+    pub fn new(grid: &'a Grid, mine_concentration: f64) -> Solver<'a> {
+        Solver { grid, mine_concentration }
+    }
+
+    /// Analyses the numbers within `area`. Flags are trusted to be mines.
+    pub fn analyse(&self, area: RectI32) -> Analysis {
+        let mut constraints = self.constraints(area);
+        let mut frontier = Vec::new();
+        for constraint in constraints.iter() {
+            for &cell in constraint.cells.iter() {
+                if !frontier.contains(&cell) {
+                    frontier.push(cell);
+                }
+            }
+        }
+
+        let mut known = HashMap::new();
+        let mut findings = Vec::new();
+        loop {
+            constraints = constraints.into_iter()
+                .filter_map(|constraint| constraint.reduce(&known, &findings))
+                .collect();
+            if Self::single(&constraints, &mut known, &mut findings) { continue; }
+            if Self::pair  (&constraints, &mut known, &mut findings) { continue; }
+            break;
+        }
+
+        let mut probabilities: HashMap<PlaceI32, f64> = findings.iter()
+            .map(|finding| (finding.place, match finding.verdict {
+                Verdict::Safe => 0f64,
+                Verdict::Mine => 1f64,
+            }))
+            .collect();
+
+        let mine_ratio = self.mine_concentration / (1f64 - self.mine_concentration);
+        for component in Self::components(&constraints) {
+            let enumeration = Enumeration::run(
+                &component, mine_ratio, Self::MAX_ENUMERATION_STEPS,
+            );
+            let Some(enumeration) = enumeration else {
+                // too large to enumerate; fall back to the tightest local ratio
+                for constraint in component.iter() {
+                    let ratio = constraint.mines as f64 / constraint.cells.len() as f64;
+                    for &cell in constraint.cells.iter() {
+                        let probability = probabilities.entry(cell).or_insert(0f64);
+                        *probability = probability.max(ratio);
+                    }
+                }
+                continue;
+            };
+            let mut sources = Vec::new();
+            for constraint in component.iter() {
+                merge_sources(&mut sources, &constraint.sources);
+            }
+            for (index, &cell) in enumeration.cells.iter().enumerate() {
+                probabilities.insert(cell, enumeration.probability(index));
+                let Some(verdict) = enumeration.verdict(index) else { continue; };
+                findings.push(Finding {
+                    place: cell,
+                    verdict,
+                    rule: Rule::Enumeration,
+                    sources: sources.clone(),
+                });
+            }
+        }
+
+        Analysis { findings, frontier, probabilities }
+    }
+
+    /// Picks the certain move closest to `cursor` within `radius`,
+    /// preferring safe cells over mines, or else the least risky guess.
+    pub fn hint(&self, cursor: PlaceI32, radius: i32) -> Option<Hint> {
+        let area = RectI32::around(cursor, radius);
+        let analysis = self.analyse(area);
+        let distance = |place: PlaceI32| {
+            (place.x - cursor.x).pow(2) + (place.y - cursor.y).pow(2)
+        };
+
+        let certain = analysis.findings.iter()
+            .min_by_key(|finding| (finding.verdict == Verdict::Mine, distance(finding.place)));
+        if let Some(finding) = certain {
+            return Some(Hint::Certain(finding.clone()));
+        }
+
+        let frontier_guess = analysis.frontier.iter()
+            .map(|&place| (place, analysis.probabilities[&place]))
+            .min_by(|(a, a_probability), (b, b_probability)| {
+                a_probability.total_cmp(b_probability)
+                    .then(distance(*a).cmp(&distance(*b)))
+            });
+
+        // a cell no number touches is only as risky as the prior
+        let unconstrained_guess = area.places()
+            .filter(|&place| matches!(self.knowledge(place), Knowledge::Hidden))
+            .filter(|place| !analysis.frontier.contains(place))
+            .min_by_key(|&place| distance(place))
+            .map(|place| (place, self.mine_concentration));
+
+        frontier_guess.into_iter().chain(unconstrained_guess)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(place, probability)| Hint::Guess { place, probability })
+    }
+
+    fn constraints(&self, area: RectI32) -> Vec<Constraint> {
+        let mut constraints = Vec::new();
+        for place in area.places() {
+            let Knowledge::Clear = self.knowledge(place) else { continue; };
+            let mut cells = Vec::new();
+            let mut flagged = 0;
+            for neighbour in self.grid.neighbours(place) {
+                match self.knowledge(neighbour) {
+                    Knowledge::Hidden => cells.push(neighbour),
+                    Knowledge::Flagged | Knowledge::Mine => flagged += 1,
+                    Knowledge::Clear => (),
+                }
+            }
+            if cells.is_empty() { continue; }
+
+            let number = Game::mine_count(self.grid, place).count();
+            let mines = number as i32 - flagged;
+            let source = Source { place, number };
+            if let Some(constraint) = Constraint::new(cells, mines, vec![source]) {
+                constraints.push(constraint);
+            }
+        }
+        constraints
+    }
+
+    fn knowledge(&self, place: PlaceI32) -> Knowledge {
+        match self.grid.peek_state(place) {
+            CellState::Hidden  => Knowledge::Hidden,
+            CellState::Flagged => Knowledge::Flagged,
+            CellState::Revealed => match self.grid.peek(place).value {
+                CellValue::Mine  => Knowledge::Mine,
+                CellValue::Empty => Knowledge::Clear,
+            },
+        }
+    }
+
+    fn learn(
+        known: &mut HashMap<PlaceI32, usize>, findings: &mut Vec<Finding>,
+        cells: &[PlaceI32], verdict: Verdict, rule: Rule, sources: &[Source],
+    ) -> bool {
+        let mut learned = false;
+        for &cell in cells {
+            if known.contains_key(&cell) { continue; }
+            known.insert(cell, findings.len());
+            findings.push(Finding { place: cell, verdict, rule, sources: sources.to_vec() });
+            learned = true;
+        }
+        learned
+    }
+
+    fn single(
+        constraints: &[Constraint],
+        known: &mut HashMap<PlaceI32, usize>,
+        findings: &mut Vec<Finding>,
+    ) -> bool {
+        let mut learned = false;
+        for constraint in constraints {
+            let Some(verdict) = constraint.settled() else { continue; };
+            learned |= Self::learn(
+                known, findings, &constraint.cells,
+                verdict, Rule::Single, &constraint.sources,
+            );
+        }
+        learned
+    }
+
+    fn pair(
+        constraints: &[Constraint],
+        known: &mut HashMap<PlaceI32, usize>,
+        findings: &mut Vec<Finding>,
+    ) -> bool {
+        let mut by_cell: HashMap<PlaceI32, Vec<usize>> = HashMap::new();
+        for (index, constraint) in constraints.iter().enumerate() {
+            for &cell in constraint.cells.iter() {
+                by_cell.entry(cell).or_default().push(index);
+            }
+        }
+
+        let mut learned = false;
+        for (index, a) in constraints.iter().enumerate() {
+            let mut overlapping: Vec<usize> = a.cells.iter()
+                .flat_map(|cell| by_cell[cell].iter().copied())
+                .filter(|&other| other != index)
+                .collect();
+            overlapping.sort_unstable();
+            overlapping.dedup();
+
+            for other in overlapping {
+                let b = &constraints[other];
+                let only_a = a.difference(b);
+                let only_b = b.difference(a);
+                // b has at least `b.mines - a.mines` mines outside of a;
+                // if that fills all of them, a has none outside of b
+                if b.mines - a.mines != only_b.len() as i32 { continue; }
+
+                let mut sources = a.sources.clone();
+                merge_sources(&mut sources, &b.sources);
+                learned |= Self::learn(
+                    known, findings, &only_b, Verdict::Mine, Rule::Pair, &sources,
+                );
+                learned |= Self::learn(
+                    known, findings, &only_a, Verdict::Safe, Rule::Pair, &sources,
+                );
+            }
+        }
+        learned
+    }
+
+    /// Groups constraints that share cells, transitively.
+    fn components(constraints: &[Constraint]) -> Vec<Vec<&Constraint>> {
+        let mut component_of = vec![None; constraints.len()];
+        let mut components = Vec::new();
+        for start in 0..constraints.len() {
+            if component_of[start].is_some() { continue; }
+            let id = components.len();
+            component_of[start] = Some(id);
+            let mut component = vec![&constraints[start]];
+            let mut next = 0;
+            while next < component.len() {
+                let current = component[next];
+                next += 1;
+                for (index, other) in constraints.iter().enumerate() {
+                    if component_of[index].is_some() || !current.overlaps(other) {
+                        continue;
+                    }
+                    component_of[index] = Some(id);
+                    component.push(other);
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+}
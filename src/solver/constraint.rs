@@ -0,0 +1,68 @@
+use super::{merge_sources, Finding, Source, Verdict};
+use crate::helper::PlaceI32;
+use std::collections::HashMap;
+
+/// Exactly `mines` of `cells` are mines.
+#[derive(Clone, Debug)]
+pub struct Constraint {
+    pub cells: Vec<PlaceI32>,
+    pub mines: i32,
+    pub sources: Vec<Source>,
+}
+
+impl Constraint {
+    /// Returns `None` if the constraint is empty or cannot be satisfied,
+    /// which happens when the player has placed incorrect flags.
+    pub fn new(cells: Vec<PlaceI32>, mines: i32, sources: Vec<Source>) -> Option<Constraint> {
+        if cells.is_empty() || mines < 0 || mines > cells.len() as i32 {
+            return None;
+        }
+        Some(Constraint { cells, mines, sources })
+    }
+
+    /// Removes cells that are already known, inheriting the sources
+    /// of the findings that settled them.
+    pub fn reduce(
+        self,
+        known: &HashMap<PlaceI32, usize>,
+        findings: &[Finding],
+    ) -> Option<Constraint> {
+        let mut mines = self.mines;
+        let mut sources = self.sources;
+        let mut cells = Vec::new();
+        for cell in self.cells {
+            let Some(&index) = known.get(&cell) else {
+                cells.push(cell);
+                continue;
+            };
+            let finding = &findings[index];
+            if let Verdict::Mine = finding.verdict {
+                mines -= 1;
+            }
+            merge_sources(&mut sources, &finding.sources);
+        }
+        Constraint::new(cells, mines, sources)
+    }
+
+    pub fn settled(&self) -> Option<Verdict> {
+        if self.mines == 0 {
+            Some(Verdict::Safe)
+        } else if self.mines == self.cells.len() as i32 {
+            Some(Verdict::Mine)
+        } else {
+            None
+        }
+    }
+
+    /// Cells of `self` that are not in `other`.
+    pub fn difference(&self, other: &Constraint) -> Vec<PlaceI32> {
+        self.cells.iter()
+            .filter(|cell| !other.cells.contains(cell))
+            .copied()
+            .collect()
+    }
+
+    pub fn overlaps(&self, other: &Constraint) -> bool {
+        self.cells.iter().any(|cell| other.cells.contains(cell))
+    }
+}
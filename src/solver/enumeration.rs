@@ -0,0 +1,137 @@
+use super::Verdict;
+use super::constraint::Constraint;
+use crate::helper::PlaceI32;
+
+/// Every arrangement of mines consistent with a set of constraints,
+/// weighted by how likely the prior makes it.
+#[derive(Debug)]
+pub struct Enumeration {
+    pub cells: Vec<PlaceI32>,
+    solutions: u64,
+    mine_solutions: Vec<u64>,
+    total_weight: f64,
+    mine_weights: Vec<f64>,
+}
+
+struct Search {
+    cell_constraints: Vec<Vec<usize>>,
+    targets: Vec<i32>,
+    assigned_mines: Vec<i32>,
+    unassigned: Vec<i32>,
+    assignment: Vec<bool>,
+    mine_ratio: f64,
+    steps: u32,
+    max_steps: u32,
+}
+
+impl Enumeration {
+    /// `mine_ratio` is the prior odds of a cell being a mine. Returns
+    /// `None` if the search exceeds `max_steps` or finds no arrangement.
+    pub fn run(
+        constraints: &[&Constraint],
+        mine_ratio: f64,
+        max_steps: u32,
+    ) -> Option<Enumeration> {
+        let mut cells: Vec<PlaceI32> = Vec::new();
+        let mut cell_constraints: Vec<Vec<usize>> = Vec::new();
+        for (index, constraint) in constraints.iter().enumerate() {
+            for &cell in constraint.cells.iter() {
+                let position = cells.iter().position(|&known| known == cell)
+                    .unwrap_or_else(|| {
+                        cells.push(cell);
+                        cell_constraints.push(Vec::new());
+                        cells.len() - 1
+                    });
+                cell_constraints[position].push(index);
+            }
+        }
+
+        let mut enumeration = Enumeration {
+            solutions: 0,
+            mine_solutions: vec![0; cells.len()],
+            total_weight: 0f64,
+            mine_weights: vec![0f64; cells.len()],
+            cells,
+        };
+        let mut search = Search {
+            targets:        constraints.iter().map(|constraint| constraint.mines).collect(),
+            assigned_mines: vec![0; constraints.len()],
+            unassigned:     constraints.iter().map(|constraint| constraint.cells.len() as i32).collect(),
+            assignment:     vec![false; enumeration.cells.len()],
+            cell_constraints,
+            mine_ratio,
+            steps: 0,
+            max_steps,
+        };
+
+        if !search.search(&mut enumeration, 0, 1f64) || enumeration.solutions == 0 {
+            return None;
+        }
+        Some(enumeration)
+    }
+
+    pub fn probability(&self, index: usize) -> f64 {
+        if self.total_weight > 0f64 {
+            self.mine_weights[index] / self.total_weight
+        } else {
+            self.mine_solutions[index] as f64 / self.solutions as f64
+        }
+    }
+
+    pub fn verdict(&self, index: usize) -> Option<Verdict> {
+        match self.mine_solutions[index] {
+            0 => Some(Verdict::Safe),
+            count if count == self.solutions => Some(Verdict::Mine),
+            _ => None,
+        }
+    }
+}
+
+impl Search {
+    /// Returns `false` if the step budget ran out.
+    fn search(&mut self, enumeration: &mut Enumeration, index: usize, weight: f64) -> bool {
+        self.steps += 1;
+        if self.steps > self.max_steps {
+            return false;
+        }
+
+        if index == self.assignment.len() {
+            enumeration.solutions    += 1;
+            enumeration.total_weight += weight;
+            for (cell, &mine) in self.assignment.iter().enumerate() {
+                if mine {
+                    enumeration.mine_solutions[cell] += 1;
+                    enumeration.mine_weights[cell]   += weight;
+                }
+            }
+            return true;
+        }
+
+        for mine in [false, true] {
+            self.assignment[index] = mine;
+            let mut consistent = true;
+            for &constraint in self.cell_constraints[index].iter() {
+                self.unassigned[constraint] -= 1;
+                if mine { self.assigned_mines[constraint] += 1; }
+                let assigned = self.assigned_mines[constraint];
+                if assigned > self.targets[constraint] ||
+                   assigned + self.unassigned[constraint] < self.targets[constraint] {
+                    consistent = false;
+                }
+            }
+
+            let weight = if mine { weight * self.mine_ratio } else { weight };
+            let finished = !consistent || self.search(enumeration, index + 1, weight);
+
+            for &constraint in self.cell_constraints[index].iter() {
+                self.unassigned[constraint] += 1;
+                if mine { self.assigned_mines[constraint] -= 1; }
+            }
+            if !finished {
+                return false;
+            }
+        }
+        self.assignment[index] = false;
+        true
+    }
+}
@@ -0,0 +1,141 @@
+use super::{Rule, Solver, Source, Verdict};
+use crate::grid::Grid;
+use crate::grid::cell::CellValue;
+use crate::grid::cell_builder::CellBuilder;
+use crate::grid::generator::{GeneratorKind, Layout};
+use crate::grid::topology::Topology;
+use crate::helper::{PlaceI32, RectI32, SizeI32};
+
+const MINE_CONCENTRATION: f64 = 0.2;
+
+/// A world repeating `layout`, and a function from places in the layout,
+/// counted from its bottom left corner, to places in the world.
+fn world(layout: &str) -> (Grid, impl Fn(i32, i32) -> PlaceI32) {
+    let rows: Vec<&str> = layout.lines().rev().collect();
+    let (width, height) = (rows[0].len() as i32, rows.len() as i32);
+    let cell_builder = CellBuilder::new(
+        MINE_CONCENTRATION, Some(0),
+        &GeneratorKind::Layout(Layout::parse(layout).unwrap()),
+        GeneratorKind::VERSION, None, Topology::Square,
+        |message: &'static str| panic!("{}", message),
+    );
+    let grid = Grid::new(cell_builder);
+    // the start moves the layout, so find where it went
+    let fits = |corner: PlaceI32| rows.iter().enumerate().all(|(y, row)| {
+        row.chars().enumerate().all(|(x, character)| {
+            let place = PlaceI32 { x: corner.x + x as i32, y: corner.y + y as i32 };
+            matches!(
+                (grid.peek(place).value, character),
+                (CellValue::Mine, '*') | (CellValue::Empty, '.'),
+            )
+        })
+    });
+    let corner = (0..width)
+        .flat_map(|x| (0..height).map(move |y| PlaceI32 { x, y }))
+        .find(|&corner| fits(corner))
+        .expect("the layout is somewhere in its world");
+    (grid, move |x, y| PlaceI32 { x: corner.x + x, y: corner.y + y })
+}
+
+fn reveal(grid: &mut Grid, places: impl IntoIterator<Item = PlaceI32>) {
+    for place in places {
+        grid.get_mut(place).reveal();
+    }
+}
+
+fn flag(grid: &mut Grid, places: impl IntoIterator<Item = PlaceI32>) {
+    for place in places {
+        grid.get_mut(place).flag();
+    }
+}
+
+/// Two numbers side by side against flagged mines, under the hidden
+/// cells p, q and r at 1,2 to 3,2, of which only p is a mine.
+const PAIR: &str = "\
+.........
+.........
+**.......
+*..*.....
+****.....";
+
+fn pair_world() -> (Grid, impl Fn(i32, i32) -> PlaceI32) {
+    let (mut grid, at) = world(PAIR);
+    reveal(&mut grid, [at(1, 1), at(2, 1)]);
+    flag(&mut grid, [at(0, 0), at(1, 0), at(2, 0), at(3, 0), at(0, 1), at(3, 1), at(0, 2)]);
+    (grid, at)
+}
+
+fn area(at: impl Fn(i32, i32) -> PlaceI32) -> RectI32 {
+    RectI32 { corner: at(0, 0), size: SizeI32 { width: 5, height: 3 } }
+}
+
+#[test]
+fn single_finds_mines() {
+    let (mut grid, at) = pair_world();
+    reveal(&mut grid, [at(2, 2)]);
+    let analysis = Solver::new(&grid, MINE_CONCENTRATION).analyse(area(&at));
+    let finding = analysis.finding(at(1, 2)).expect("p is found");
+    assert_eq!(finding.verdict, Verdict::Mine);
+    assert!(matches!(finding.rule, Rule::Single));
+    assert_eq!(finding.sources, [Source { place: at(1, 1), number: 6 }]);
+}
+
+#[test]
+fn single_finds_safe_cells() {
+    let (mut grid, at) = pair_world();
+    flag(&mut grid, [at(1, 2)]);
+    let analysis = Solver::new(&grid, MINE_CONCENTRATION).analyse(area(&at));
+    for place in [at(2, 2), at(3, 2)] {
+        let finding = analysis.finding(place).expect("q and r are found");
+        assert_eq!(finding.verdict, Verdict::Safe);
+        assert!(matches!(finding.rule, Rule::Single));
+    }
+}
+
+#[test]
+fn pair_finds_what_single_cannot() {
+    let (grid, at) = pair_world();
+    let analysis = Solver::new(&grid, MINE_CONCENTRATION).analyse(area(&at));
+    assert_eq!(analysis.findings.len(), 1);
+    let finding = analysis.finding(at(3, 2)).expect("r is found");
+    assert_eq!(finding.verdict, Verdict::Safe);
+    assert!(matches!(finding.rule, Rule::Pair));
+    assert_eq!(finding.sources, [
+        Source { place: at(2, 1), number: 5 },
+        Source { place: at(1, 1), number: 6 },
+    ]);
+    // either of p and q is the mine, and both ways have one mine
+    assert_eq!(analysis.mine_probability(at(1, 2)), Some(0.5));
+    assert_eq!(analysis.mine_probability(at(2, 2)), Some(0.5));
+}
+
+/// Two numbers that each see one mine among two hidden cells, sharing
+/// q at 2,2, which is the mine.
+const SHARED: &str = "\
+.........
+.........
+*.*.*....
+*.*.*....
+*****....";
+
+#[test]
+fn enumeration_weighs_by_the_prior() {
+    let (mut grid, at) = world(SHARED);
+    reveal(&mut grid, [at(1, 1), at(3, 1)]);
+    flag(&mut grid, (0..5).map(|x| at(x, 0)));
+    flag(&mut grid, [at(0, 1), at(2, 1), at(4, 1), at(0, 2), at(4, 2)]);
+    let analysis = Solver::new(&grid, MINE_CONCENTRATION).analyse(area(&at));
+    assert!(analysis.findings.is_empty());
+
+    // q alone has one mine, p and r together two, which the prior makes
+    // less likely by the odds of a mine
+    let odds = MINE_CONCENTRATION / (1f64 - MINE_CONCENTRATION);
+    let q = 1f64 / (1f64 + odds);
+    let close = |place: PlaceI32, expected: f64| {
+        let probability = analysis.mine_probability(place).expect("place is on the frontier");
+        assert!((probability - expected).abs() < 1e-9, "{probability} is not {expected}");
+    };
+    close(at(2, 2), q);
+    close(at(1, 2), 1f64 - q);
+    close(at(3, 2), 1f64 - q);
+}
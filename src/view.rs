@@ -5,11 +5,23 @@ use crossterm::terminal::{Clear, ClearType};
 use crossterm::QueueableCommand;
 use crate::grid::cell::{Cell, CellState, CellValue};
 use crate::game::{Game, GameState, MineCount};
+use crate::game::auto_play::{AutoPlay, AutoPlayState, Speed};
+use crate::game::multiplayer::{Mode, Multiplayer, Player};
+use crate::game::daily::Date;
+use crate::game::world_code::WorldCode;
 use crate::grid::Grid;
+use crate::grid::density::DensityCurve;
+use crate::grid::generator::GeneratorKind;
+use crate::grid::topology::Topology;
 use self::matrix::Matrix;
 use crate::helper::{PlaceI32, PlaceUsize, SizeUsize};
+use crate::solver::{Analysis, Hint, Verdict};
 
 mod matrix;
+pub mod export;
+pub mod virtual_terminal;
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug)]
 pub enum ViewCell {
@@ -18,22 +30,27 @@ pub enum ViewCell {
     Clear,
     Mine,
     IncorrectFlag,
-    One, Two, Three, Four,
-    Five, Six, Seven, Eight,
+    /// a revealed cell with from 1 to `Topology::MAX_MINE_COUNT` mines
+    /// around it
+    Number(u8),
 }
 
 impl ViewCell {
-    pub const fn char(&self) -> &'static str {
-        match self {
+    /// Characters of mine counts, with letters from 10 on so every count
+    /// is one character. Like world codes, it skips I, L and O.
+    pub const DIGITS: &str = "0123456789ABCDEFGHJKMNPQR";
+
+    pub fn char(&self) -> &'static str {
+        match *self {
             ViewCell::Unrevealed    => " ",
             ViewCell::Flagged       => "+",
             ViewCell::Clear         => "0",
             ViewCell::Mine          => "*",
             ViewCell::IncorrectFlag => "X",
-            ViewCell::One   => "1", ViewCell::Two   => "2",
-            ViewCell::Three => "3", ViewCell::Four  => "4",
-            ViewCell::Five  => "5", ViewCell::Six   => "6",
-            ViewCell::Seven => "7", ViewCell::Eight => "8",
+            ViewCell::Number(count) => {
+                let count = count as usize;
+                &Self::DIGITS[count..count + 1]
+            },
         }
     }
 
@@ -47,18 +64,52 @@ impl ViewCell {
             ViewCell::Mine    => Color::Rgb { r: 0xbd, g: 0xbd, b: 0xbd },
             ViewCell::Unrevealed    => Color::Rgb { r: 0x00, g: 0x00, b: 0x00 },
             ViewCell::IncorrectFlag => Color::Rgb { r: 0xff, g: 0x00, b: 0x00 },
-            ViewCell::One   => Color::Rgb { r: 0x00, g: 0x00, b: 0xff },
-            ViewCell::Two   => Color::Rgb { r: 0x00, g: 0x7b, b: 0x00 },
-            ViewCell::Three => Color::Rgb { r: 0xff, g: 0x00, b: 0x00 },
-            ViewCell::Four  => Color::Rgb { r: 0x00, g: 0x00, b: 0x7b },
-            ViewCell::Five  => Color::Rgb { r: 0x7b, g: 0x00, b: 0x00 },
-            ViewCell::Six   => Color::Rgb { r: 0x00, g: 0x7b, b: 0x7b },
-            ViewCell::Seven => Color::Rgb { r: 0x00, g: 0x00, b: 0x00 },
-            ViewCell::Eight => Color::Rgb { r: 0x7b, g: 0x7b, b: 0x7b },
+            ViewCell::Number(1) => Color::Rgb { r: 0x00, g: 0x00, b: 0xff },
+            ViewCell::Number(2) => Color::Rgb { r: 0x00, g: 0x7b, b: 0x00 },
+            ViewCell::Number(3) => Color::Rgb { r: 0xff, g: 0x00, b: 0x00 },
+            ViewCell::Number(4) => Color::Rgb { r: 0x00, g: 0x00, b: 0x7b },
+            ViewCell::Number(5) => Color::Rgb { r: 0x7b, g: 0x00, b: 0x00 },
+            ViewCell::Number(6) => Color::Rgb { r: 0x00, g: 0x7b, b: 0x7b },
+            ViewCell::Number(7) => Color::Rgb { r: 0x00, g: 0x00, b: 0x00 },
+            ViewCell::Number(8) => Color::Rgb { r: 0x7b, g: 0x7b, b: 0x7b },
+            // only radius 2 counts this many
+            ViewCell::Number(_) => Color::Rgb { r: 0x7b, g: 0x00, b: 0x7b },
         }
     }
 }
 
+/// What a view is drawn from, gathered by `Game::view`.
+#[derive(Debug)]
+pub struct ViewConfig<'a> {
+    pub grid: &'a Grid,
+    pub window_size: SizeUsize,
+    pub window_too_small: bool,
+    pub origin: PlaceI32,
+    pub game_cursor: PlaceI32,
+    pub show_mines: bool,
+    pub revealed_cell_count: u32,
+    pub start_instant: time::Instant,
+    /// when the game ended, or now if it hasn't
+    pub latest_game_instant: time::Instant,
+    pub game_state: GameState,
+    pub seed: u64,
+    pub light_mode: bool,
+    pub hint: Option<Hint>,
+    pub hints_used: u32,
+    /// the analysis behind the overlay, and whether to show the
+    /// probability under the cursor
+    pub heat_map: Option<(&'a Analysis, bool)>,
+    pub mine_concentration: f64,
+    pub generator: &'a GeneratorKind,
+    pub version: u8,
+    pub density_curve: Option<DensityCurve>,
+    pub topology: Topology,
+    pub auto_play: AutoPlay,
+    pub multiplayer: Option<&'a Multiplayer>,
+    pub daily: Option<Date>,
+    pub notice: Option<String>,
+}
+
 #[derive(Debug)]
 pub struct View {
     matrix: Matrix<ViewCell>,
@@ -69,37 +120,121 @@ pub struct View {
     revealed_cell_count: u32,
     game_duration: time::Duration,
     game_state: GameState,
-    seed: u64,
+    /// how to play this world again, shown once the game is over
+    world: String,
     light_mode: bool,
+    hint: Option<Hint>,
+    hints_used: u32,
+    heat: Option<Matrix<Option<f64>>>,
+    cursor_probability: Option<f64>,
+    /// mine concentration around the cursor, when it varies
+    local_mine_concentration: Option<f64>,
+    auto_play: AutoPlay,
+    /// decides which rows are drawn half a cell to the right
+    topology: Topology,
+    /// other players' cursors within the matrix
+    remote_cursors: Vec<(PlaceUsize, Color)>,
+    multiplayer_title: Option<String>,
+    eliminated: bool,
+    /// standings of a race, replacing the title
+    race: Option<String>,
+    daily: Option<Date>,
+    notice: Option<String>,
 }
 
 impl View {
     const FOREGROUND_COLOR_LIGHT_MODE: Color = Color::Rgb { r: 0x00, g: 0x00, b: 0x00 };
-    const BACKGROUND_COLOR_LIGHT_MODE: Color = Color::Rgb { r: 0xbd, g: 0xbd, b: 0xbd };
-
-    pub fn new(
-        grid: &Grid,                        window_size: SizeUsize,
-        window_too_small: bool,             origin: PlaceI32,
-        game_cursor: PlaceI32,              show_mines: bool,
-        revealed_cell_count: u32,           start_instant: time::Instant,
-        latest_game_instant: time::Instant, game_state: GameState,
-        seed: u64,                          light_mode: bool,
-    ) -> View {
+    pub const BACKGROUND_COLOR_LIGHT_MODE: Color = Color::Rgb { r: 0xbd, g: 0xbd, b: 0xbd };
+    const BACKGROUND_COLOR_HINT_SAFE:   Color = Color::Rgb { r: 0x00, g: 0xbd, b: 0x00 };
+    const BACKGROUND_COLOR_HINT_MINE:   Color = Color::Rgb { r: 0xff, g: 0x55, b: 0x55 };
+    const BACKGROUND_COLOR_HINT_GUESS:  Color = Color::Rgb { r: 0xff, g: 0xa5, b: 0x00 };
+    const BACKGROUND_COLOR_HINT_SOURCE: Color = Color::Rgb { r: 0xff, g: 0xff, b: 0x7b };
+    /// Sources listed in a hint before the rest are elided.
+    const HINT_SOURCES_SHOWN: usize = 3;
+    /// Cursor colours of other players, by player id.
+    const PLAYER_COLORS: [Color; 6] = [
+        Color::Rgb { r: 0xff, g: 0x55, b: 0x55 },
+        Color::Rgb { r: 0x55, g: 0x99, b: 0xff },
+        Color::Rgb { r: 0x00, g: 0xbd, b: 0x00 },
+        Color::Rgb { r: 0xff, g: 0xa5, b: 0x00 },
+        Color::Rgb { r: 0xbd, g: 0x55, b: 0xff },
+        Color::Rgb { r: 0x00, g: 0xbd, b: 0xbd },
+    ];
+
+    pub fn new(config: ViewConfig) -> View {
+        let ViewConfig {
+            grid,                window_size,
+            window_too_small,    origin,
+            game_cursor,         show_mines,
+            revealed_cell_count, start_instant,
+            latest_game_instant, game_state,
+            seed,                light_mode,
+            hint,                hints_used,
+            heat_map,            mine_concentration,
+            generator,           version,
+            density_curve,       topology,
+            auto_play,           multiplayer,
+            daily,               notice,
+        } = config;
         let matrix_size = Self::matrix_size(window_size);
+        let cell_position = |relative: PlaceUsize| PlaceI32 {
+            x: origin.x - matrix_size.width  as i32 / 2 + relative.x as i32,
+            y: origin.y - matrix_size.height as i32 / 2 + relative.y as i32,
+        };
         let matrix = Matrix::new(
             matrix_size,
             |relative: PlaceUsize| {
-                let cell_position = PlaceI32 {
-                    x: origin.x - matrix_size.width  as i32 / 2 + relative.x as i32,
-                    y: origin.y - matrix_size.height as i32 / 2 + relative.y as i32,
-                };
-                Self::get_view_cell(grid, cell_position, show_mines)
+                Self::get_view_cell(grid, cell_position(relative), show_mines)
             },
         );
         let matrix_cursor = PlaceUsize {
             x: (game_cursor.x + matrix_size.width  as i32 / 2 - origin.x) as usize,
             y: (game_cursor.y + matrix_size.height as i32 / 2 - origin.y) as usize,
         };
+        let heat = heat_map.map(|(analysis, _)| Matrix::new(
+            matrix_size,
+            |relative: PlaceUsize| match matrix.get(relative) {
+                ViewCell::Unrevealed => analysis.mine_probability(cell_position(relative)),
+                _ => None,
+            },
+        ));
+        let cursor_probability = match heat_map {
+            Some((analysis, true)) if !window_too_small => match matrix.get(matrix_cursor) {
+                ViewCell::Unrevealed => Some(
+                    analysis.mine_probability(game_cursor)
+                        .unwrap_or_else(|| grid.mine_concentration(game_cursor)),
+                ),
+                _ => None,
+            },
+            _ => None,
+        };
+        let remote_cursors = multiplayer.into_iter()
+            .filter(|multiplayer| multiplayer.shares_grid())
+            .flat_map(|multiplayer| multiplayer.others())
+            .filter_map(|player| {
+                let x = player.cursor.x + matrix_size.width  as i32 / 2 - origin.x;
+                let y = player.cursor.y + matrix_size.height as i32 / 2 - origin.y;
+                if x < 0 || y < 0 || x >= matrix_size.width as i32 || y >= matrix_size.height as i32 {
+                    return None;
+                }
+                let color = Self::PLAYER_COLORS[player.id as usize % Self::PLAYER_COLORS.len()];
+                Some((PlaceUsize { x: x as usize, y: y as usize }, color))
+            })
+            .collect();
+        let multiplayer_title = multiplayer.and_then(Self::multiplayer_title);
+        let eliminated = multiplayer.is_some_and(Multiplayer::eliminated);
+        let race = multiplayer.filter(|multiplayer| !multiplayer.shares_grid())
+            .map(Self::race_standings);
+        let code = WorldCode::new(seed, mine_concentration, generator, version, topology);
+        let world = match (code, generator, density_curve) {
+            (_, GeneratorKind::Layout(_), _) => "CUSTOM LAYOUT".to_string(),
+            (_, _, Some(DensityCurve { far, radius })) => format!(
+                "SEED {seed} CONCENTRATION {mine_concentration} TO {far} AT {radius}",
+            ),
+            (Some(code), _, None) => format!("WORLD {code}"),
+            (None, _, None) => format!("SEED {seed} CONCENTRATION {mine_concentration}"),
+        };
+        let local_mine_concentration = density_curve.map(|_| grid.mine_concentration(game_cursor));
         let game_duration = latest_game_instant.duration_since(start_instant);
         View {
             matrix,
@@ -110,14 +245,34 @@ impl View {
             revealed_cell_count,
             game_duration,
             game_state,
-            seed,
+            world,
             light_mode,
+            hint,
+            hints_used,
+            heat,
+            cursor_probability,
+            local_mine_concentration,
+            auto_play,
+            topology,
+            remote_cursors,
+            multiplayer_title,
+            eliminated,
+            race,
+            daily,
+            notice,
         }
     }
 
-    fn get_view_cell(grid: &Grid, place: PlaceI32, show_mines: bool) -> ViewCell {
-        let cell = grid.get(place);
-        match *cell {
+    pub fn get_view_cell(grid: &Grid, place: PlaceI32, show_mines: bool) -> ViewCell {
+        let (cell, mine_count) = match grid.peek_stored(place) {
+            Some(stored) => stored,
+            // hidden cells look alike, so there's no need to generate them
+            None if !show_mines && grid.peek_state(place) == CellState::Hidden => {
+                return ViewCell::Unrevealed;
+            },
+            None => (grid.peek(place), None),
+        };
+        match cell {
             Cell {
                 state: CellState::Hidden,
                 value: CellValue::Mine,
@@ -129,12 +284,13 @@ impl View {
             Cell { state: CellState::Hidden,  .. } => ViewCell::Unrevealed,
             Cell { state: CellState::Flagged, .. } => ViewCell::Flagged,
             Cell { value: CellValue::Mine,    .. } => ViewCell::Mine,
-            Cell { value: CellValue::Empty,   .. } => match Game::mine_count(grid, place) {
-                MineCount::Zero  => ViewCell::Clear,
-                MineCount::One   => ViewCell::One,   MineCount::Two   => ViewCell::Two,
-                MineCount::Three => ViewCell::Three, MineCount::Four  => ViewCell::Four,
-                MineCount::Five  => ViewCell::Five,  MineCount::Six   => ViewCell::Six,
-                MineCount::Seven => ViewCell::Seven, MineCount::Eight => ViewCell::Eight,
+            Cell { value: CellValue::Empty,   .. } => {
+                // revealing stores the number, so this is usually known already
+                let mine_count = mine_count.unwrap_or_else(|| grid.mine_count(place));
+                match Game::to_mine_count(mine_count) {
+                    MineCount::ZERO => ViewCell::Clear,
+                    mine_count => ViewCell::Number(mine_count.count()),
+                }
             },
         }
     }
@@ -165,37 +321,63 @@ impl View {
             return Ok(());
         }
 
-        let line = if let GameState::Underway = self.game_state {
-            format!(
+        let title = match (self.game_state, self.eliminated) {
+            _ if self.race.is_some() => self.race.as_ref().map(|race| {
+                race.chars().take(self.window_size.width).collect()
+            }),
+            (GameState::Lost,     _)    => Some("GAME OVER".to_string()),
+            (GameState::Underway, true) => Some("ELIMINATED".to_string()),
+            (GameState::Underway, false) => self.auto_play_title()
+                .or_else(|| self.multiplayer_title.clone())
+                .or_else(|| self.daily.map(|date| format!("DAILY {date}"))),
+        };
+        let line = match title {
+            None => format!(
                 "{:<pad_dist$}{}",
                 "SCORE",
                 "TIME",
                 pad_dist = self.window_size.width - "TIME".len(),
-            )
-        } else if self.window_size.width >= 21 {
-            let pad_left   = (self.window_size.width - 9) / 2;
-            let pad_right  = self.window_size.width - 9 - pad_left;
-            format!(
-                "{:<pad_left$}{}{:>pad_right$}",
-                "SCORE",
-                "GAME OVER",
-                "TIME",
-            )
-        } else {
-            format!(
+            ),
+            Some(title) if self.window_size.width >= title.len() + 12 => {
+                let pad_left   = (self.window_size.width - title.len()) / 2;
+                let pad_right  = self.window_size.width - title.len() - pad_left;
+                format!(
+                    "{:<pad_left$}{}{:>pad_right$}",
+                    "SCORE",
+                    title,
+                    "TIME",
+                )
+            },
+            Some(title) => format!(
                 "{:^width$}",
-                "GAME OVER",
+                title,
                 width = self.window_size.width,
-            )
+            ),
         };
         self.render_line(buffer, 0, &line)?;
 
-        let time = self.game_duration.as_secs();
-        let line = format!(
-            "{:<pad_dist$}{time}",
-            self.revealed_cell_count.to_string(),
-            pad_dist = self.window_size.width - time.to_string().len(),
-        );
+        let time  = self.game_duration.as_secs().to_string();
+        let score = self.revealed_cell_count.to_string();
+        let hints = match self.hints_used {
+            0 => None,
+            1 => Some("1 HINT".to_string()),
+            hints_used => Some(format!("{hints_used} HINTS")),
+        };
+        let density = self.local_mine_concentration
+            .map(|concentration| format!("{:.0}% MINES", concentration * 100f64));
+        let hints = [hints, density].into_iter().flatten().collect::<Vec<String>>().join("  ");
+        let line = if hints.is_empty() ||
+            score.len() + hints.len() + time.len() + 2 > self.window_size.width {
+            format!(
+                "{:<pad_dist$}{time}",
+                score,
+                pad_dist = self.window_size.width - time.len(),
+            )
+        } else {
+            let pad_left  = (self.window_size.width - hints.len()) / 2;
+            let pad_right = self.window_size.width - hints.len() - pad_left;
+            format!("{score:<pad_left$}{hints}{time:>pad_right$}")
+        };
         self.render_line(buffer, 1, &line)?;
 
         let mut line = String::new(); 
@@ -208,7 +390,7 @@ impl View {
             let line_no = self.matrix.size.height - y + 2;
             self.render_character(
                 buffer, line_no, 0,
-                (Self::FAT_LEFT_BORDER, None),
+                (Self::FAT_LEFT_BORDER, None, None),
             )?;
 
             for x in 0..(self.window_size.width - 2) {
@@ -218,7 +400,7 @@ impl View {
             }
             self.render_character(
                 buffer, line_no, self.window_size.width - 1,
-                (Self::FAT_RIGHT_BORDER, None),
+                (Self::FAT_RIGHT_BORDER, None, None),
             )?;
         }
 
@@ -228,24 +410,38 @@ impl View {
         line +=  Self::FAT_BOTTOM_RIGHT_CORNER;
         self.render_line(buffer, self.matrix.size.height + 3, &line)?;
 
-        let line = format!(
-            "{:>pad_dist$},{:<pad_dist$}",
-            format!("({}", self.game_cursor.x),
-            format!("{})", self.game_cursor.y),
-            pad_dist = self.window_size.width / 2 - 1,
-        );
+        let line = if let Some(notice) = &self.notice {
+            let text: String = notice.chars()
+                .take(self.window_size.width)
+                .collect();
+            format!("{:^width$}", text, width = self.window_size.width)
+        } else if let GameState::Lost = self.game_state {
+            let text: String = self.world.chars()
+                .take(self.window_size.width)
+                .collect();
+            format!("{:^width$}", text, width = self.window_size.width)
+        } else if let Some(hint) = &self.hint {
+            let text: String = Self::hint_text(hint).chars()
+                .take(self.window_size.width)
+                .collect();
+            format!("{:^width$}", text, width = self.window_size.width)
+        } else if let Some(probability) = self.cursor_probability {
+            format!(
+                "{:>pad_dist$},{:<pad_dist$}",
+                format!("({}", self.game_cursor.x),
+                format!("{}) {:.0}%", self.game_cursor.y, probability * 100f64),
+                pad_dist = self.window_size.width / 2 - 1,
+            )
+        } else {
+            format!(
+                "{:>pad_dist$},{:<pad_dist$}",
+                format!("({}", self.game_cursor.x),
+                format!("{})", self.game_cursor.y),
+                pad_dist = self.window_size.width / 2 - 1,
+            )
+        };
         self.render_line(buffer, self.matrix.size.height + 4, &line)?;
 
-        let _ = self.seed;
-        // let mut line = String::new();
-        // line += &format!(
-        //     "{:<pad_dist$}0x{:X}",
-        //     "SEED",
-        //     self.seed,
-        //     pad_dist = self.window_size.width - "0x0123456789ABCDEF".len(),
-        // );
-        // Self::render_line(buffer, self.matrix.size.height + 5, &line)?;
-
         Ok(())
     }
 
@@ -262,7 +458,8 @@ impl View {
 
     fn render_character(
         &self, buffer: &mut impl io::Write, line: usize,
-        column: usize, (character, color): (&str, Option<Color>),
+        column: usize,
+        (character, color, background): (&str, Option<Color>, Option<Color>),
     ) -> io::Result<()> {
         buffer.queue(MoveTo(
             column.try_into().expect("column number above u16 integer limit"),
@@ -276,11 +473,128 @@ impl View {
         if let Some(color) = color {
             buffer.queue(SetForegroundColor(color))?;
         }
+        if let Some(background) = background {
+            buffer.queue(SetBackgroundColor(background))?;
+        }
         buffer.queue(Print(character))?;
         Ok(())
     }
 
-    fn get_character_and_color(&self, place: PlaceUsize) -> (&'static str, Option<Color>) {
+    fn auto_play_title(&self) -> Option<String> {
+        match (self.auto_play.state, self.auto_play.speed) {
+            (AutoPlayState::Off,     _) => None,
+            (AutoPlayState::Paused,  _) => Some("AUTO PAUSED".to_string()),
+            (AutoPlayState::Running, Speed::Instant) => Some("AUTO MAX".to_string()),
+            (AutoPlayState::Running, Speed::StepsPerSecond(steps_per_second)) =>
+                Some(format!("AUTO {steps_per_second}/S")),
+        }
+    }
+
+    fn multiplayer_title(multiplayer: &Multiplayer) -> Option<String> {
+        match multiplayer.mode {
+            Mode::Race { .. } => None,
+            Mode::Publish if multiplayer.spectating() && multiplayer.following => Some("FOLLOWING".to_string()),
+            Mode::Publish if multiplayer.spectating() => Some("SPECTATING".to_string()),
+            Mode::Publish => Some(format!("{} WATCHING", multiplayer.connections())),
+            Mode::Coop { .. } => match multiplayer.players.len() {
+                1 => Some("1 PLAYER".to_string()),
+                players => Some(format!("{players} PLAYERS")),
+            },
+        }
+    }
+
+    /// Scores of every player in a race, starting with the local one.
+    fn race_standings(multiplayer: &Multiplayer) -> String {
+        let label = |player: &Player| match player.id {
+            id if id == multiplayer.player => "YOU".to_string(),
+            id => format!("P{id}"),
+        };
+        let status = |player: &Player| match (player.eliminated, player.finished) {
+            (true, _)      => " OUT",
+            (false, true)  => " DONE",
+            (false, false) => "",
+        };
+        let mut players: Vec<&Player> = multiplayer.players.iter().collect();
+        players.sort_by_key(|player| player.id != multiplayer.player);
+        let standings = players.into_iter()
+            .map(|player| format!("{} {}{}", label(player), player.score, status(player)))
+            .collect::<Vec<String>>()
+            .join("  ");
+        match multiplayer.winner() {
+            Some(winner) if winner.id == multiplayer.player => format!("YOU WIN  {standings}"),
+            Some(winner) => format!("{} WINS  {standings}", label(winner)),
+            None => standings,
+        }
+    }
+
+    fn hint_text(hint: &Hint) -> String {
+        match hint {
+            Hint::Certain(finding) => {
+                let verdict = match finding.verdict {
+                    Verdict::Safe => "safe",
+                    Verdict::Mine => "a mine",
+                };
+                let mut sources = finding.sources.iter()
+                    .take(Self::HINT_SOURCES_SHOWN)
+                    .map(|source| format!(
+                        "{} at ({},{})",
+                        source.number, source.place.x, source.place.y,
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                if finding.sources.len() > Self::HINT_SOURCES_SHOWN {
+                    sources += ", ...";
+                }
+                format!(
+                    "({},{}) is {verdict}: see {sources}",
+                    finding.place.x, finding.place.y,
+                )
+            },
+            Hint::Guess { place, probability } => format!(
+                "no certain move; ({},{}) is a mine with {:.0}% chance",
+                place.x, place.y, probability * 100f64,
+            ),
+        }
+    }
+
+    /// Gradient from green through yellow to red.
+    fn heat_color(probability: f64) -> Color {
+        let probability = probability.clamp(0f64, 1f64);
+        if probability < 0.5f64 {
+            Color::Rgb { r: (probability * 2f64 * 255f64) as u8, g: 0xbd, b: 0x00 }
+        } else {
+            Color::Rgb { r: 0xff, g: ((1f64 - probability) * 2f64 * 189f64) as u8, b: 0x00 }
+        }
+    }
+
+    fn background(&self, place: PlaceI32, matrix_place: PlaceUsize) -> Option<Color> {
+        let heat = self.heat.as_ref()
+            .and_then(|heat| *heat.get(matrix_place))
+            .map(Self::heat_color);
+        let Some(hint) = self.hint.as_ref() else { return heat; };
+        match hint {
+            Hint::Certain(finding) if finding.place == place => match finding.verdict {
+                Verdict::Safe => Some(Self::BACKGROUND_COLOR_HINT_SAFE),
+                Verdict::Mine => Some(Self::BACKGROUND_COLOR_HINT_MINE),
+            },
+            Hint::Certain(finding) if finding.sources.iter()
+                .any(|source| source.place == place) => Some(Self::BACKGROUND_COLOR_HINT_SOURCE),
+            Hint::Guess { place: guess, .. } if *guess == place => Some(Self::BACKGROUND_COLOR_HINT_GUESS),
+            _ => heat,
+        }
+    }
+
+    fn get_character_and_color(
+        &self, place: PlaceUsize,
+    ) -> (&'static str, Option<Color>, Option<Color>) {
+        // shifted rows lose their last cell at the right border
+        let game_y = self.game_cursor.y + place.y as i32 - self.matrix_cursor.y as i32;
+        let shift = self.topology.tiling().shifted(game_y) as usize;
+        if place.x < shift {
+            return (Self::SPACE, None, None);
+        }
+        let place = PlaceUsize { x: place.x - shift, y: place.y };
+
         let cursor = PlaceUsize {
             x: self.matrix_cursor.x * 2 + 1,
             y: self.matrix_cursor.y,
@@ -297,13 +611,19 @@ impl View {
             // (-1,  0) =>  return Self::SLIM_LEFT_BORDER,
             // ( 1,  0) =>  return Self::SLIM_RIGHT_BORDER,
             // // top/bottom border would overwrite adjacent cells
-            (-1,  0) =>  return ("[", None),
-            ( 1,  0) =>  return ("]", None),
+            (-1,  0) =>  return ("[", None, None),
+            ( 1,  0) =>  return ("]", None, None),
             _ => (),
         }
 
+        for &(remote_cursor, color) in self.remote_cursors.iter() {
+            if place.y != remote_cursor.y { continue; }
+            if place.x == remote_cursor.x * 2     { return ("[", Some(color), None); }
+            if place.x == remote_cursor.x * 2 + 2 { return ("]", Some(color), None); }
+        }
+
         if place.x % 2 != 1 {
-            return (Self::SPACE, None);
+            return (Self::SPACE, None, None);
         }
 
         let matrix_place = PlaceUsize {
@@ -312,8 +632,16 @@ impl View {
         };
 
         let view_cell = self.matrix.get(matrix_place);
+        let game_place = PlaceI32 {
+            x: self.game_cursor.x + matrix_place.x as i32 - self.matrix_cursor.x as i32,
+            y: self.game_cursor.y + matrix_place.y as i32 - self.matrix_cursor.y as i32,
+        };
 
-        (view_cell.char(), Some(view_cell.color(self.light_mode)))
+        (
+            view_cell.char(),
+            Some(view_cell.color(self.light_mode)),
+            self.background(game_place, matrix_place),
+        )
     }
 
     pub fn matrix_size(window_size: SizeUsize) -> SizeUsize {
@@ -0,0 +1,206 @@
+use crate::grid::Grid;
+use crate::grid::topology::{Tiling, Topology};
+use crate::helper::{PlaceI32, RectI32};
+use crate::view::{View, ViewCell};
+use crossterm::style::Color;
+use std::io;
+
+#[cfg(test)]
+mod tests;
+
+/// Picture of part of the grid, drawn with the glyphs and colours of
+/// the terminal, for runs too large to fit on a screen.
+#[derive(Debug)]
+pub struct Export {
+    area: RectI32,
+    /// cells by row from the top, then by column
+    cells: Vec<ViewCell>,
+    topology: Topology,
+    light_mode: bool,
+}
+
+impl Export {
+    const CELL_WIDTH:  usize = 8;
+    const CELL_HEIGHT: usize = 10;
+    const GLYPH_WIDTH:  usize = 5;
+    const GLYPH_HEIGHT: usize = 7;
+    const BACKGROUND_COLOR: Color = Color::Rgb { r: 0x00, g: 0x00, b: 0x00 };
+    /// Largest area that is exported, which makes an image of about 80 MB
+    /// before compression.
+    pub const MAX_CELLS: u64 = 1 << 20;
+
+    /// Exports `area`, or everything that has been revealed or flagged.
+    /// Returns `None` if there is nothing to export.
+    pub fn new(
+        grid: &Grid, area: Option<RectI32>,
+        show_mines: bool, light_mode: bool,
+    ) -> io::Result<Option<Export>> {
+        let Some(area) = area.or_else(|| grid.bounding_box()) else { return Ok(None); };
+        if area.size.width <= 0 || area.size.height <= 0 { return Ok(None); }
+        if area.size.width as u64 * area.size.height as u64 > Self::MAX_CELLS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("area is larger than {} cells", Self::MAX_CELLS),
+            ));
+        }
+        if area.corner.x.checked_add(area.size.width).is_none() ||
+           area.corner.y.checked_add(area.size.height).is_none() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "area goes past the largest coordinate"));
+        }
+        let cells = (0..area.size.height).rev()
+            .flat_map(|y| (0..area.size.width).map(move |x| PlaceI32 {
+                x: area.corner.x + x,
+                y: area.corner.y + y,
+            }))
+            .map(|place| View::get_view_cell(grid, place, show_mines))
+            .collect();
+        Ok(Some(Export { area, cells, topology: grid.topology(), light_mode }))
+    }
+
+    /// Width of the picture, with room for rows shifted by half a cell.
+    fn width(&self) -> usize {
+        let shift = match self.topology.tiling() {
+            Tiling::Square => 0,
+            Tiling::Hex => Self::CELL_WIDTH / 2,
+        };
+        self.area.size.width as usize * Self::CELL_WIDTH + shift
+    }
+
+    /// Left edge of the cell at `index` in `cells`.
+    fn left(&self, index: usize) -> usize {
+        let columns = self.area.size.width as usize;
+        let (column, row) = (index % columns, index / columns);
+        let y = self.area.corner.y + self.area.size.height - 1 - row as i32;
+        let shift = if self.topology.tiling().shifted(y) { Self::CELL_WIDTH / 2 } else { 0 };
+        column * Self::CELL_WIDTH + shift
+    }
+
+    fn background(&self) -> Color {
+        if self.light_mode { View::BACKGROUND_COLOR_LIGHT_MODE } else { Self::BACKGROUND_COLOR }
+    }
+
+    fn rgb(color: Color) -> [u8; 3] {
+        match color {
+            Color::Rgb { r, g, b } => [r, g, b],
+            _ => unreachable!(), // cells are only coloured with rgb
+        }
+    }
+
+    pub fn svg(&self, mut buffer: impl io::Write) -> io::Result<()> {
+        let width  = self.width();
+        let height = self.area.size.height as usize * Self::CELL_HEIGHT;
+        let [r, g, b] = Self::rgb(self.background());
+        writeln!(
+            buffer,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#,
+        )?;
+        writeln!(buffer, r##"<rect width="100%" height="100%" fill="#{r:02x}{g:02x}{b:02x}"/>"##)?;
+        writeln!(
+            buffer,
+            r#"<g font-family="monospace" font-size="{}" text-anchor="middle">"#,
+            Self::CELL_HEIGHT,
+        )?;
+        for (index, cell) in self.cells.iter().enumerate() {
+            if let ViewCell::Unrevealed = cell { continue; }
+            let row = index / self.area.size.width as usize;
+            let [r, g, b] = Self::rgb(cell.color(self.light_mode));
+            writeln!(
+                buffer,
+                r##"<text x="{}" y="{}" fill="#{r:02x}{g:02x}{b:02x}">{}</text>"##,
+                self.left(index) + Self::CELL_WIDTH / 2,
+                (row + 1) * Self::CELL_HEIGHT - 2,
+                cell.char(),
+            )?;
+        }
+        writeln!(buffer, "</g>")?;
+        writeln!(buffer, "</svg>")
+    }
+
+    pub fn png(&self, buffer: impl io::Write) -> io::Result<()> {
+        let columns = self.area.size.width as usize;
+        let width   = self.width();
+        let height  = self.area.size.height as usize * Self::CELL_HEIGHT;
+
+        let mut palette = vec![Self::rgb(self.background())];
+        let mut pixels = vec![0u8; width * height];
+        for (index, cell) in self.cells.iter().enumerate() {
+            let Some(glyph) = Self::glyph(cell) else { continue; };
+            let color = Self::rgb(cell.color(self.light_mode));
+            let color_index = match palette.iter().position(|&other| other == color) {
+                Some(color_index) => color_index,
+                None => {
+                    palette.push(color);
+                    palette.len() - 1
+                },
+            } as u8;
+            let left = self.left(index)                      + (Self::CELL_WIDTH  - Self::GLYPH_WIDTH)  / 2;
+            let top  = (index / columns) * Self::CELL_HEIGHT + (Self::CELL_HEIGHT - Self::GLYPH_HEIGHT) / 2;
+            for (y, row) in glyph.iter().enumerate() {
+                for x in 0..Self::GLYPH_WIDTH {
+                    if row >> (Self::GLYPH_WIDTH - 1 - x) & 1 == 1 {
+                        pixels[(top + y) * width + left + x] = color_index;
+                    }
+                }
+            }
+        }
+
+        // 4 bits per pixel, two to a byte
+        assert!(palette.len() <= 16, "too many colours for a 4 bit palette");
+        let rows: Vec<u8> = pixels.chunks(width.max(1))
+            .flat_map(|row| row.chunks(2).map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0)))
+            .collect();
+
+        let size_error = || io::Error::other("image is too large");
+        let mut encoder = png::Encoder::new(
+            buffer,
+            width .try_into().map_err(|_| size_error())?,
+            height.try_into().map_err(|_| size_error())?,
+        );
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Four);
+        encoder.set_palette(palette.concat());
+        encoder.write_header()?.write_image_data(&rows)?;
+        Ok(())
+    }
+
+    /// Pixels of `ViewCell::DIGITS`, one row of 5 bits per line.
+    const DIGIT_GLYPHS: [[u8; Self::GLYPH_HEIGHT]; 25] = [
+        [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110], // 0
+        [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110], // 1
+        [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111], // 2
+        [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110], // 3
+        [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010], // 4
+        [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110], // 5
+        [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110], // 6
+        [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000], // 7
+        [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110], // 8
+        [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100], // 9
+        [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001], // A
+        [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110], // B
+        [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110], // C
+        [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100], // D
+        [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111], // E
+        [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000], // F
+        [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111], // G
+        [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001], // H
+        [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100], // J
+        [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001], // K
+        [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001], // M
+        [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001], // N
+        [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000], // P
+        [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101], // Q
+        [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001], // R
+    ];
+
+    /// Pixels of a cell's character, one row of 5 bits per line.
+    fn glyph(cell: &ViewCell) -> Option<[u8; Self::GLYPH_HEIGHT]> {
+        Some(match *cell {
+            ViewCell::Unrevealed    => return None,
+            ViewCell::Clear         => Self::DIGIT_GLYPHS[0],
+            ViewCell::Number(count) => Self::DIGIT_GLYPHS[count as usize],
+            ViewCell::Flagged       => [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000],
+            ViewCell::Mine          => [0b00000, 0b10101, 0b01110, 0b11111, 0b01110, 0b10101, 0b00000],
+            ViewCell::IncorrectFlag => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        })
+    }
+}
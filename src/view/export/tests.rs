@@ -0,0 +1,49 @@
+use super::Export;
+use crate::grid::Grid;
+use crate::grid::cell_builder::CellBuilder;
+use crate::grid::generator::GeneratorKind;
+use crate::grid::topology::Topology;
+use crate::helper::{PlaceI32, RectI32, SizeI32};
+
+const SEED: u64 = 1;
+const MINE_CONCENTRATION: f64 = 0.2;
+const VERSION: u8 = 1;
+
+fn grid() -> Grid {
+    let cell_builder = CellBuilder::new(
+        MINE_CONCENTRATION, Some(SEED), &GeneratorKind::Uniform, VERSION, None, Topology::Square,
+        |message: &'static str| panic!("{}", message),
+    );
+    Grid::new(cell_builder.revealed())
+}
+
+fn area(x: i32, y: i32, width: i32, height: i32) -> Option<RectI32> {
+    Some(RectI32 { corner: PlaceI32 { x, y }, size: SizeI32 { width, height } })
+}
+
+#[test]
+fn exports_refuse_huge_areas() {
+    let grid = grid();
+    assert!(Export::new(&grid, area(-20, -10, 40, 21), false, false).unwrap().is_some());
+    assert!(Export::new(&grid, area(0, 0, 1 << 10, (1 << 10) + 1), false, false).is_err());
+    assert!(Export::new(&grid, area(i32::MAX - 10, 0, 20, 20), false, false).is_err());
+}
+
+#[test]
+fn pngs_decode_to_the_exported_area() {
+    let (width, height) = (5, 3);
+    let export = Export::new(&grid(), area(-2, -1, width, height), false, false).unwrap().unwrap();
+    let mut image = Vec::new();
+    export.png(&mut image).unwrap();
+
+    let mut reader = png::Decoder::new(std::io::Cursor::new(image)).read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+    let frame = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(frame.width,  width  as u32 * Export::CELL_WIDTH  as u32);
+    assert_eq!(frame.height, height as u32 * Export::CELL_HEIGHT as u32);
+    assert_eq!(frame.color_type, png::ColorType::Indexed);
+    // the background and at least one glyph colour
+    let palette = reader.info().palette.as_ref().unwrap();
+    assert!(palette.len() >= 2 * 3);
+    assert_eq!(palette[..3], Export::rgb(Export::BACKGROUND_COLOR));
+}
@@ -12,11 +12,11 @@ impl<T> Matrix<T> {
         mut builder: F,
     ) -> Matrix<T> {
         let data =
-            (0..size.width).map(
+            (0..size.width).flat_map(
                 |x| (0..size.height).map(
-                    |y| builder(PlaceUsize { x: x, y: y }),
+                    |y| builder(PlaceUsize { x, y }),
                 ).collect::<Box<[T]>>(),
-            ).flatten().collect();
+            ).collect();
         Matrix {
             size,
             data,
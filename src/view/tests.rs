@@ -0,0 +1,74 @@
+use crate::game::{Action, Direction, Game, WorldConfig};
+use crate::game::auto_play::AutoPlay;
+use crate::grid::generator::GeneratorKind;
+use crate::grid::topology::Topology;
+use crate::helper::SizeUsize;
+use crate::view::virtual_terminal::VirtualTerminal;
+use std::{env, fs};
+
+const SEED: u64 = 1;
+const MINE_CONCENTRATION: f64 = 0.2;
+/// the snapshots also check that version 0 still makes the same world
+const VERSION: u8 = 0;
+
+fn game(window_size: SizeUsize, light_mode: bool) -> Game {
+    let config = WorldConfig {
+        mine_concentration: MINE_CONCENTRATION,
+        seed: Some(SEED),
+        generator: GeneratorKind::Uniform,
+        version: VERSION,
+        density_curve: None,
+        topology: Topology::Square,
+    };
+    Game::new(
+        config, window_size, light_mode,
+        AutoPlay::new(false, 0, false), None,
+    )
+}
+
+fn screen(game: &Game) -> VirtualTerminal {
+    let view = game.view();
+    let mut terminal = VirtualTerminal::new(view.window_size);
+    view.render(&mut terminal).unwrap();
+    terminal
+}
+
+/// Compares the screen with the snapshot stored in `snapshots/`. Run the
+/// tests with `UPDATE_SNAPSHOTS=1` to write the snapshots instead.
+fn assert_snapshot(name: &str, game: &Game) {
+    let path = format!("{}/src/view/snapshots/{name}.txt", env!("CARGO_MANIFEST_DIR"));
+    let actual = screen(game).snapshot();
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("no snapshot at {path}, run with UPDATE_SNAPSHOTS=1"));
+    assert!(actual == expected, "screen does not match {path}:\n{actual}");
+}
+
+#[test]
+fn normal() {
+    assert_snapshot("normal", &game(Game::HEADLESS_WINDOW_SIZE, false));
+}
+
+#[test]
+fn lost() {
+    let mut game = game(Game::HEADLESS_WINDOW_SIZE, false);
+    // walk right, revealing every cell, until a mine is hit
+    while !screen(&game).line(0).contains("GAME OVER") {
+        game.action(Action::MoveCursor(Direction::Right));
+        game.action(Action::Reveal);
+    }
+    assert_snapshot("lost", &game);
+}
+
+#[test]
+fn light_mode() {
+    assert_snapshot("light_mode", &game(Game::HEADLESS_WINDOW_SIZE, true));
+}
+
+#[test]
+fn too_small() {
+    assert_snapshot("too_small", &game(SizeUsize { width: 16, height: 8 }, false));
+}
@@ -0,0 +1,209 @@
+use crate::helper::{PlaceUsize, SizeUsize};
+use crossterm::style::Color;
+use std::io;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TerminalCell {
+    pub character: char,
+    /// `None` for the terminal's default colour
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+}
+
+impl TerminalCell {
+    const BLANK: TerminalCell = TerminalCell {
+        character: ' ',
+        foreground: None,
+        background: None,
+    };
+}
+
+/// A screen in memory that interprets the escape sequences `View`
+/// writes, so that rendering can be inspected and tested.
+#[derive(Debug)]
+pub struct VirtualTerminal {
+    pub size: SizeUsize,
+    cells: Vec<TerminalCell>,
+    cursor: PlaceUsize,
+    foreground: Option<Color>,
+    background: Option<Color>,
+    /// bytes of an escape sequence or character that isn't complete yet
+    pending: Vec<u8>,
+}
+
+impl VirtualTerminal {
+    pub fn new(size: SizeUsize) -> VirtualTerminal {
+        VirtualTerminal {
+            size,
+            cells: vec![TerminalCell::BLANK; size.width * size.height],
+            cursor: PlaceUsize { x: 0, y: 0 },
+            foreground: None,
+            background: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Cell at a column and line, counted from the top left.
+    pub fn get(&self, place: PlaceUsize) -> TerminalCell {
+        self.cells[place.y * self.size.width + place.x]
+    }
+
+    pub fn line(&self, line: usize) -> String {
+        (0..self.size.width)
+            .map(|x| self.get(PlaceUsize { x, y: line }).character)
+            .collect()
+    }
+
+    /// The characters on the screen, followed by its colours with a
+    /// letter for each distinct colour and `.` for the default colour.
+    pub fn snapshot(&self) -> String {
+        let mut colors: Vec<Color> = Vec::new();
+        let mut letter = |color: Option<Color>| {
+            let Some(color) = color else { return '.'; };
+            let index = match colors.iter().position(|&other| other == color) {
+                Some(index) => index,
+                None => {
+                    colors.push(color);
+                    colors.len() - 1
+                },
+            };
+            (b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ")[index % 52] as char
+        };
+
+        let mut snapshot = String::from("screen:\n");
+        for y in 0..self.size.height {
+            snapshot += &format!("|{}|\n", self.line(y));
+        }
+        for (name, layer) in [
+            ("foreground", (|cell: TerminalCell| cell.foreground) as fn(TerminalCell) -> Option<Color>),
+            ("background", |cell: TerminalCell| cell.background),
+        ] {
+            snapshot += &format!("{name}:\n");
+            for y in 0..self.size.height {
+                let line: String = (0..self.size.width)
+                    .map(|x| letter(layer(self.get(PlaceUsize { x, y }))))
+                    .collect();
+                snapshot += &format!("|{line}|\n");
+            }
+        }
+        snapshot += "colors:\n";
+        for (index, color) in colors.iter().enumerate() {
+            let letter = (b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ")[index % 52] as char;
+            match color {
+                Color::Rgb { r, g, b } => snapshot += &format!("{letter} #{r:02x}{g:02x}{b:02x}\n"),
+                color => snapshot += &format!("{letter} {color:?}\n"),
+            }
+        }
+        snapshot
+    }
+
+    fn print(&mut self, character: char) {
+        // wrap like a terminal does at the end of a line
+        if self.cursor.x >= self.size.width {
+            self.cursor = PlaceUsize { x: 0, y: self.cursor.y + 1 };
+        }
+        if self.cursor.y >= self.size.height { return; }
+        self.cells[self.cursor.y * self.size.width + self.cursor.x] = TerminalCell {
+            character,
+            foreground: self.foreground,
+            background: self.background,
+        };
+        self.cursor.x += 1;
+    }
+
+    /// Interprets a control sequence, given the bytes between `ESC [` and
+    /// its final byte.
+    fn control(&mut self, parameters: &str, command: char) {
+        let numbers: Vec<u16> = parameters.split(';')
+            .map(|number| number.parse().unwrap_or(0))
+            .collect();
+        match command {
+            // cursor position, counted from 1
+            'H' => {
+                let line   = numbers.first().copied().unwrap_or(1).max(1) as usize - 1;
+                let column = numbers.get(1) .copied().unwrap_or(1).max(1) as usize - 1;
+                self.cursor = PlaceUsize { x: column, y: line };
+            },
+            'J' if numbers[0] == 2 => self.cells.fill(TerminalCell::BLANK),
+            'm' => self.select_graphic_rendition(&numbers),
+            _ => (), // showing the cursor and the like don't change the screen
+        }
+    }
+
+    fn select_graphic_rendition(&mut self, numbers: &[u16]) {
+        let mut numbers = numbers.iter().copied();
+        while let Some(number) = numbers.next() {
+            let target = match number {
+                0 => {
+                    self.foreground = None;
+                    self.background = None;
+                    continue;
+                },
+                39 => { self.foreground = None; continue; },
+                49 => { self.background = None; continue; },
+                38 => &mut self.foreground,
+                48 => &mut self.background,
+                _ => continue,
+            };
+            match numbers.next() {
+                Some(2) => {
+                    let mut channel = || numbers.next().unwrap_or(0) as u8;
+                    let (r, g, b) = (channel(), channel(), channel());
+                    *target = Some(Color::Rgb { r, g, b });
+                },
+                Some(5) => *target = Some(Color::AnsiValue(numbers.next().unwrap_or(0) as u8)),
+                _ => (),
+            }
+        }
+    }
+
+    fn interpret(&mut self) {
+        let mut consumed = 0;
+        loop {
+            let rest = &self.pending[consumed..];
+            let Some(&first) = rest.first() else { break; };
+            if first == 0x1b {
+                if rest.len() < 2 { break; }
+                if rest[1] != b'[' {
+                    consumed += 2;
+                    continue;
+                }
+                let Some(end) = rest[2..].iter().position(|byte| (0x40..=0x7e).contains(byte)) else { break; };
+                let parameters = String::from_utf8_lossy(&rest[2..2 + end]).into_owned();
+                let command = rest[2 + end] as char;
+                consumed += 3 + end;
+                self.control(&parameters, command);
+                continue;
+            }
+            let length = match first {
+                0x00..=0x7f => 1,
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                _ => 4,
+            };
+            if rest.len() < length { break; }
+            let character = std::str::from_utf8(&rest[..length]).ok()
+                .and_then(|text| text.chars().next())
+                .unwrap_or(char::REPLACEMENT_CHARACTER);
+            consumed += length;
+            match character {
+                '\n' => self.cursor = PlaceUsize { x: 0, y: self.cursor.y + 1 },
+                '\r' => self.cursor.x = 0,
+                character => self.print(character),
+            }
+        }
+        self.pending.drain(..consumed);
+    }
+}
+
+impl io::Write for VirtualTerminal {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(bytes);
+        self.interpret();
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}